@@ -0,0 +1,402 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
+
+const DECIMAL_CHUNK: u32 = 1_000_000_000;
+const DECIMAL_CHUNK_DIGITS: usize = 9;
+
+/// Arbitrary-precision signed integer.
+///
+/// The magnitude is stored as little-endian base 2^32 limbs without trailing
+/// zero limbs, so zero is an empty magnitude and is never negative.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: Vec<u32>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseBigIntError;
+
+impl BigInt {
+    pub fn zero() -> Self {
+        BigInt {
+            negative: false,
+            magnitude: Vec::new(),
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn to_i32(&self) -> Option<i32> {
+        self.to_i64().and_then(|value| i32::try_from(value).ok())
+    }
+
+    pub fn to_i64(&self) -> Option<i64> {
+        if self.magnitude.len() > 2 {
+            return None;
+        }
+        let mut magnitude: u64 = 0;
+        for limb in self.magnitude.iter().rev() {
+            magnitude = (magnitude << 32) | *limb as u64;
+        }
+        if self.negative {
+            0i64.checked_sub_unsigned(magnitude)
+        } else {
+            i64::try_from(magnitude).ok()
+        }
+    }
+
+    /// Parses unsigned `digits` in `radix` from 2 to 36, e.g. `ff` in radix 16.
+    pub fn from_str_radix(digits: &str, radix: u32) -> Result<BigInt, ParseBigIntError> {
+        if digits.is_empty() {
+            return Err(ParseBigIntError);
+        }
+        let mut magnitude = Vec::new();
+        for digit in digits.chars() {
+            let digit = digit.to_digit(radix).ok_or(ParseBigIntError)?;
+            mul_add_small(&mut magnitude, radix, digit);
+        }
+        Ok(BigInt::from_parts(false, magnitude))
+    }
+
+    /// Division truncating toward zero, like `i32::checked_div`.
+    /// Returns `None` when dividing by zero.
+    pub fn checked_div(&self, divisor: &BigInt) -> Option<BigInt> {
+        if divisor.is_zero() {
+            return None;
+        }
+        let (quotient, _) = divide_magnitudes(&self.magnitude, &divisor.magnitude);
+        Some(BigInt::from_parts(
+            self.negative != divisor.negative,
+            quotient,
+        ))
+    }
+
+    fn from_parts(negative: bool, mut magnitude: Vec<u32>) -> Self {
+        while magnitude.last() == Some(&0) {
+            magnitude.pop();
+        }
+        let negative = negative && !magnitude.is_empty();
+        BigInt {
+            negative,
+            magnitude,
+        }
+    }
+}
+
+fn compare_magnitudes(first: &[u32], second: &[u32]) -> Ordering {
+    first
+        .len()
+        .cmp(&second.len())
+        .then_with(|| first.iter().rev().cmp(second.iter().rev()))
+}
+
+fn add_magnitudes(first: &[u32], second: &[u32]) -> Vec<u32> {
+    let (longer, shorter) = if first.len() >= second.len() {
+        (first, second)
+    } else {
+        (second, first)
+    };
+    let mut result = Vec::with_capacity(longer.len() + 1);
+    let mut carry = 0u64;
+    for (index, limb) in longer.iter().enumerate() {
+        let sum = *limb as u64 + *shorter.get(index).unwrap_or(&0) as u64 + carry;
+        result.push(sum as u32);
+        carry = sum >> 32;
+    }
+    if carry > 0 {
+        result.push(carry as u32);
+    }
+    result
+}
+
+/// Subtracts `second` from `first`, where `first` must not be smaller.
+fn sub_magnitudes(first: &[u32], second: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(first.len());
+    let mut borrow = 0i64;
+    for (index, limb) in first.iter().enumerate() {
+        let mut difference = *limb as i64 - *second.get(index).unwrap_or(&0) as i64 - borrow;
+        borrow = 0;
+        if difference < 0 {
+            difference += 1 << 32;
+            borrow = 1;
+        }
+        result.push(difference as u32);
+    }
+    result
+}
+
+fn mul_magnitudes(first: &[u32], second: &[u32]) -> Vec<u32> {
+    let mut result = vec![0u32; first.len() + second.len()];
+    for (i, a) in first.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, b) in second.iter().enumerate() {
+            let current = result[i + j] as u64 + *a as u64 * *b as u64 + carry;
+            result[i + j] = current as u32;
+            carry = current >> 32;
+        }
+        result[i + second.len()] = carry as u32;
+    }
+    result
+}
+
+/// Binary long division of magnitudes, returns quotient and remainder.
+fn divide_magnitudes(dividend: &[u32], divisor: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    let mut quotient = vec![0u32; dividend.len()];
+    let mut remainder: Vec<u32> = Vec::new();
+    for bit in (0..dividend.len() * 32).rev() {
+        shift_left_one(&mut remainder);
+        if (dividend[bit / 32] >> (bit % 32)) & 1 == 1 {
+            if remainder.is_empty() {
+                remainder.push(1);
+            } else {
+                remainder[0] |= 1;
+            }
+        }
+        if compare_magnitudes(&remainder, divisor) != Ordering::Less {
+            remainder = sub_magnitudes(&remainder, divisor);
+            while remainder.last() == Some(&0) {
+                remainder.pop();
+            }
+            quotient[bit / 32] |= 1 << (bit % 32);
+        }
+    }
+    (quotient, remainder)
+}
+
+fn shift_left_one(magnitude: &mut Vec<u32>) {
+    let mut carry = 0u32;
+    for limb in magnitude.iter_mut() {
+        let next_carry = *limb >> 31;
+        *limb = (*limb << 1) | carry;
+        carry = next_carry;
+    }
+    if carry > 0 {
+        magnitude.push(carry);
+    }
+}
+
+/// Computes `magnitude * multiplier + addend` in place.
+fn mul_add_small(magnitude: &mut Vec<u32>, multiplier: u32, addend: u32) {
+    let mut carry = addend as u64;
+    for limb in magnitude.iter_mut() {
+        let current = *limb as u64 * multiplier as u64 + carry;
+        *limb = current as u32;
+        carry = current >> 32;
+    }
+    if carry > 0 {
+        magnitude.push(carry as u32);
+    }
+}
+
+/// Divides in place and returns the remainder.
+fn div_rem_small(magnitude: &mut Vec<u32>, divisor: u32) -> u32 {
+    let mut remainder = 0u64;
+    for limb in magnitude.iter_mut().rev() {
+        let current = (remainder << 32) | *limb as u64;
+        *limb = (current / divisor as u64) as u32;
+        remainder = current % divisor as u64;
+    }
+    while magnitude.last() == Some(&0) {
+        magnitude.pop();
+    }
+    remainder as u32
+}
+
+impl From<i32> for BigInt {
+    fn from(value: i32) -> Self {
+        BigInt::from(value as i64)
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(value: i64) -> Self {
+        let magnitude = value.unsigned_abs();
+        BigInt::from_parts(value < 0, vec![magnitude as u32, (magnitude >> 32) as u32])
+    }
+}
+
+impl From<u64> for BigInt {
+    fn from(value: u64) -> Self {
+        BigInt::from_parts(false, vec![value as u32, (value >> 32) as u32])
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match input.as_bytes().first() {
+            Some(b'-') => (true, &input[1..]),
+            Some(b'+') => (false, &input[1..]),
+            _ => (false, input),
+        };
+        if digits.is_empty() || !digits.bytes().all(|digit| digit.is_ascii_digit()) {
+            return Err(ParseBigIntError);
+        }
+        let mut magnitude = Vec::new();
+        for digit in digits.bytes() {
+            mul_add_small(&mut magnitude, 10, (digit - b'0') as u32);
+        }
+        Ok(BigInt::from_parts(negative, magnitude))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_zero() {
+            return write!(formatter, "0");
+        }
+        let mut magnitude = self.magnitude.clone();
+        let mut chunks = Vec::new();
+        while !magnitude.is_empty() {
+            chunks.push(div_rem_small(&mut magnitude, DECIMAL_CHUNK));
+        }
+        if self.negative {
+            write!(formatter, "-")?;
+        }
+        write!(formatter, "{}", chunks.pop().unwrap())?;
+        for chunk in chunks.iter().rev() {
+            write!(formatter, "{:0width$}", chunk, width = DECIMAL_CHUNK_DIGITS)?;
+        }
+        Ok(())
+    }
+}
+
+/// Shows the decimal value instead of the limbs, e.g. in `Result: Some(BigInt(..))`.
+impl fmt::Debug for BigInt {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, formatter)
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => compare_magnitudes(&self.magnitude, &other.magnitude),
+            (true, true) => compare_magnitudes(&other.magnitude, &self.magnitude),
+        }
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.magnitude.clone())
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::from_parts(
+                self.negative,
+                add_magnitudes(&self.magnitude, &other.magnitude),
+            );
+        }
+        match compare_magnitudes(&self.magnitude, &other.magnitude) {
+            Ordering::Less => BigInt::from_parts(
+                other.negative,
+                sub_magnitudes(&other.magnitude, &self.magnitude),
+            ),
+            _ => BigInt::from_parts(
+                self.negative,
+                sub_magnitudes(&self.magnitude, &other.magnitude),
+            ),
+        }
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        self + &(-other)
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt::from_parts(
+            self.negative != other.negative,
+            mul_magnitudes(&self.magnitude, &other.magnitude),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(input: &str) -> BigInt {
+        input.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        assert_eq!(big("0").to_string(), "0");
+        assert_eq!(big("-0").to_string(), "0");
+        assert_eq!(big("+42").to_string(), "42");
+        let literal = "-123456789012345678901234567890";
+        assert_eq!(big(literal).to_string(), literal);
+        assert_eq!(format!("{:?}", big(literal)), literal);
+        assert_eq!("12a".parse::<BigInt>(), Err(ParseBigIntError));
+        assert_eq!("-".parse::<BigInt>(), Err(ParseBigIntError));
+        assert_eq!(BigInt::from_str_radix("fF", 16), Ok(BigInt::from(255)));
+        assert_eq!(
+            BigInt::from_str_radix("1000000000000000000000000000000000", 2)
+                .unwrap()
+                .to_string(),
+            "8589934592"
+        );
+        assert_eq!(BigInt::from_str_radix("18", 8), Err(ParseBigIntError));
+        assert_eq!(BigInt::from_str_radix("", 10), Err(ParseBigIntError));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let first = big("99999999999999999999");
+        let second = big("-1");
+        assert_eq!((&first + &second).to_string(), "99999999999999999998");
+        assert_eq!((&second - &first).to_string(), "-100000000000000000000");
+        assert_eq!(
+            (&first * &first).to_string(),
+            "9999999999999999999800000000000000000001"
+        );
+        assert_eq!(
+            first.checked_div(&big("-33")).unwrap().to_string(),
+            "-3030303030303030303"
+        );
+        assert_eq!(big("-7").checked_div(&big("2")).unwrap(), BigInt::from(-3));
+        assert!(first.checked_div(&BigInt::zero()).is_none());
+    }
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(BigInt::from(i32::MIN).to_i32(), Some(i32::MIN));
+        assert_eq!(BigInt::from(i64::MIN).to_i64(), Some(i64::MIN));
+        assert_eq!(big("2147483648").to_i32(), None);
+        assert!(big("-5") < big("3"));
+    }
+}
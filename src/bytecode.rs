@@ -1,8 +1,12 @@
 use crate::{
     commands::*,
-    errors::{NoCodeError, NoReturnCommandError, RuntimeError},
-    state::State,
+    errors::{LinkError, NoCodeError, NoReturnCommandError, RuntimeError},
+    native::NativeFunction,
+    sandbox::FileSandbox,
+    state::{BreakpointHandler, State},
+    value::Value,
 };
+use std::path::PathBuf;
 
 pub type CodeType = Vec<Box<dyn Command>>;
 pub struct ByteCode {
@@ -19,10 +23,61 @@ impl ByteCode {
         self.code = code;
     }
 
-    pub fn run(&mut self) -> Result<Option<i32>, RuntimeError> {
-        if self.code.len() == 0 {
+    /// Makes a host function available to `CALL_NATIVE name`. The function
+    /// receives `arity` arguments popped from the stack.
+    pub fn register_native<F>(&mut self, name: &str, arity: usize, function: F)
+    where
+        F: Fn(&[Value]) -> Result<Vec<Value>, String> + 'static,
+    {
+        let function: NativeFunction = Box::new(function);
+        self.state
+            .get_natives_mut()
+            .register(name.to_owned(), arity, function);
+    }
+
+    /// Allows `FILE_READ_INT` and `FILE_APPEND` to access files inside `root`.
+    /// Without this grant every file instruction fails.
+    pub fn grant_file_access<P: Into<PathBuf>>(&mut self, root: P) {
+        self.state.set_file_sandbox(FileSandbox::new(root));
+    }
+
+    /// Registers the host callback invoked by `BREAKPOINT`.
+    pub fn set_breakpoint_handler(&mut self, handler: BreakpointHandler) {
+        self.state.set_breakpoint_handler(handler);
+    }
+
+    /// Checks that every native function used by the code is registered.
+    /// `run` links the code before executing any instruction.
+    pub fn link(&self) -> Result<(), LinkError> {
+        for command in self.code.iter() {
+            command.link(&self.state)?;
+        }
+        Ok(())
+    }
+
+    /// Runs the code and returns the top of the stack, or `None` if the stack
+    /// is empty. For `RETURN n` only the last of the returned values is given,
+    /// use `run_values` to get all of them.
+    pub fn run(&mut self) -> Result<Option<Value>, RuntimeError> {
+        Ok(self.run_values()?.pop())
+    }
+
+    /// Runs the code and returns the values of `RETURN n` in the order they
+    /// were pushed. Plain `RETURN` gives the top of the stack, if any.
+    pub fn run_values(&mut self) -> Result<Vec<Value>, RuntimeError> {
+        self.execute()?;
+        let values = match self.state.take_return_values() {
+            Some(values) => values,
+            None => self.state.pop_from_stack_option().into_iter().collect(),
+        };
+        Ok(values)
+    }
+
+    fn execute(&mut self) -> Result<(), RuntimeError> {
+        if self.code.is_empty() {
             return Err(RuntimeError::from(NoCodeError));
         }
+        self.link()?;
         let mut cmd;
         while {
             cmd = self.code.get(self.state.get_command_pointer());
@@ -35,12 +90,18 @@ impl ByteCode {
             return Err(RuntimeError::from(NoReturnCommandError));
         }
 
-        Ok(self.state.pop_from_stack_option())
+        Ok(())
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::vec_init_then_push)]
 mod tests {
+    use super::super::bigint::BigInt;
+    use super::super::clock::{Clock, ManualClock};
+    use super::super::decimal::RoundingMode;
+    use super::super::heap::MAX_HEAP_ELEMENTS;
+    use super::super::io::{CaptureSink, VecSource};
     use super::super::simpleloop::SimpleLoop;
     use super::*;
 
@@ -61,7 +122,7 @@ mod tests {
 
         let mut interpreter = get_interpreter();
         interpreter.set_code(code);
-        assert_eq!(interpreter.run().unwrap().unwrap(), 2);
+        assert_eq!(interpreter.run().unwrap().unwrap(), Value::Int(2));
     }
 
     #[test]
@@ -74,7 +135,7 @@ mod tests {
 
         let mut interpreter = get_interpreter();
         interpreter.set_code(code);
-        assert_eq!(interpreter.run().unwrap().unwrap(), 0);
+        assert_eq!(interpreter.run().unwrap().unwrap(), Value::Int(0));
     }
 
     #[test]
@@ -87,7 +148,7 @@ mod tests {
 
         let mut interpreter = get_interpreter();
         interpreter.set_code(code);
-        assert_eq!(interpreter.run().unwrap().unwrap(), -20);
+        assert_eq!(interpreter.run().unwrap().unwrap(), Value::Int(-20));
     }
 
     #[test]
@@ -100,7 +161,538 @@ mod tests {
 
         let mut interpreter = get_interpreter();
         interpreter.set_code(code);
-        assert_eq!(interpreter.run().unwrap().unwrap(), 2);
+        assert_eq!(interpreter.run().unwrap().unwrap(), Value::Int(2));
+    }
+
+    #[test]
+    fn test_float_commands() {
+        let mut code = CodeType::new();
+        code.push(Box::new(LoadValueCommand::new(7.5, "".to_owned())));
+        code.push(Box::new(LoadValueCommand::new(2.5, "".to_owned())));
+        code.push(Box::new(FloatSubCommand::new(String::from("FSUB"))));
+        code.push(Box::new(LoadValueCommand::new(2.0, "".to_owned())));
+        code.push(Box::new(FloatDivCommand::new(String::from("FDIV"))));
+        code.push(Box::new(FloatToIntCommand::new(String::from("F2I"))));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+
+        let mut interpreter = get_interpreter();
+        interpreter.set_code(code);
+        assert_eq!(interpreter.run().unwrap().unwrap(), Value::Int(2));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_float_to_int_overflow_error() {
+        let mut code = CodeType::new();
+        code.push(Box::new(LoadValueCommand::new(1e12, "".to_owned())));
+        code.push(Box::new(FloatToIntCommand::new(String::from("F2I"))));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+
+        let mut interpreter = get_interpreter();
+        interpreter.set_code(code);
+        interpreter.run().unwrap();
+    }
+
+    #[test]
+    fn test_read_write_typed_variables() {
+        let mut code = CodeType::new();
+        code.push(Box::new(LoadValueCommand::new(true, "".to_owned())));
+        code.push(Box::new(WriteVariableCommand::new(
+            String::from("flag"),
+            String::from("WRITE_VAR flag"),
+        )));
+        code.push(Box::new(LoadValueCommand::new("text", "".to_owned())));
+        code.push(Box::new(ReadVariableCommand::new(
+            String::from("flag"),
+            String::from("READ_VAR flag"),
+        )));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+
+        let mut interpreter = get_interpreter();
+        interpreter.set_code(code);
+        assert_eq!(interpreter.run().unwrap().unwrap(), Value::Bool(true));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_type_mismatch_error() {
+        let mut code = CodeType::new();
+        code.push(Box::new(LoadValueCommand::new(1, "".to_owned())));
+        code.push(Box::new(LoadValueCommand::new(1.5, "".to_owned())));
+        code.push(Box::new(AddCommand::new(String::from("ADD"))));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+
+        let mut interpreter = get_interpreter();
+        interpreter.set_code(code);
+        interpreter.run().unwrap();
+    }
+
+    #[test]
+    fn test_decimal_commands() {
+        let mut code = CodeType::new();
+        code.push(Box::new(LoadDecimalCommand::new(
+            "10".parse().unwrap(),
+            "".to_owned(),
+        )));
+        code.push(Box::new(LoadDecimalCommand::new(
+            "3".parse().unwrap(),
+            "".to_owned(),
+        )));
+        code.push(Box::new(DecimalDivCommand::new(
+            Some(RoundingMode::Up),
+            String::from("DDIV UP"),
+        )));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+
+        let mut interpreter = get_interpreter();
+        interpreter.state.set_decimal_scale(3);
+        interpreter.set_code(code);
+        assert_eq!(interpreter.run().unwrap().unwrap().to_string(), "3.334");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_decimal_overflow_error() {
+        let mut code = CodeType::new();
+        code.push(Box::new(LoadDecimalCommand::new(
+            "92233720368547758.07".parse().unwrap(),
+            "".to_owned(),
+        )));
+        code.push(Box::new(LoadDecimalCommand::new(
+            "1".parse().unwrap(),
+            "".to_owned(),
+        )));
+        code.push(Box::new(DecimalAddCommand::new(String::from("DADD"))));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+
+        let mut interpreter = get_interpreter();
+        interpreter.set_code(code);
+        interpreter.run().unwrap();
+    }
+
+    #[test]
+    fn test_string_commands() {
+        let mut code = CodeType::new();
+        code.push(Box::new(LoadValueCommand::new("bytecode", "".to_owned())));
+        code.push(Box::new(LoadValueCommand::new(4, "".to_owned())));
+        code.push(Box::new(LoadValueCommand::new(100, "".to_owned())));
+        code.push(Box::new(SubstrCommand::new(String::from("SUBSTR"))));
+        code.push(Box::new(LenCommand::new(String::from("LEN"))));
+        code.push(Box::new(ToStrCommand::new(String::from("TO_STR"))));
+        code.push(Box::new(LoadValueCommand::new("4", "".to_owned())));
+        code.push(Box::new(StrEqCommand::new(String::from("STR_EQ"))));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+
+        let mut interpreter = get_interpreter();
+        interpreter.set_code(code);
+        assert_eq!(interpreter.run().unwrap().unwrap(), Value::Bool(true));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_string_parse_error() {
+        let mut code = CodeType::new();
+        code.push(Box::new(LoadValueCommand::new("4a", "".to_owned())));
+        code.push(Box::new(ParseIntCommand::new(String::from("PARSE_INT"))));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+
+        let mut interpreter = get_interpreter();
+        interpreter.set_code(code);
+        interpreter.run().unwrap();
+    }
+
+    #[test]
+    fn test_array_commands() {
+        let mut code = CodeType::new();
+        code.push(Box::new(NewArrayCommand::new(
+            2,
+            String::from("NEW_ARRAY 2"),
+        )));
+        code.push(Box::new(WriteVariableCommand::new(
+            String::from("a"),
+            String::from("WRITE_VAR a"),
+        )));
+        code.push(Box::new(ReadVariableCommand::new(
+            String::from("a"),
+            String::from("READ_VAR a"),
+        )));
+        code.push(Box::new(LoadValueCommand::new(1, "".to_owned())));
+        code.push(Box::new(LoadValueCommand::new(7, "".to_owned())));
+        code.push(Box::new(ArraySetCommand::new(String::from("ARR_SET"))));
+        code.push(Box::new(ReadVariableCommand::new(
+            String::from("a"),
+            String::from("READ_VAR a"),
+        )));
+        code.push(Box::new(LoadValueCommand::new(1, "".to_owned())));
+        code.push(Box::new(ArrayGetCommand::new(String::from("ARR_GET"))));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+
+        let mut interpreter = get_interpreter();
+        interpreter.set_code(code);
+        assert_eq!(interpreter.run().unwrap().unwrap(), Value::Int(7));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_out_of_bounds_error() {
+        let mut code = CodeType::new();
+        code.push(Box::new(NewArrayCommand::new(
+            2,
+            String::from("NEW_ARRAY 2"),
+        )));
+        code.push(Box::new(LoadValueCommand::new(2, "".to_owned())));
+        code.push(Box::new(ArrayGetCommand::new(String::from("ARR_GET"))));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+
+        let mut interpreter = get_interpreter();
+        interpreter.set_code(code);
+        interpreter.run().unwrap();
+    }
+
+    #[test]
+    fn test_heap_limit_error() {
+        let mut code = CodeType::new();
+        code.push(Box::new(NewArrayCommand::new(
+            i32::MAX as usize,
+            String::from("NEW_ARRAY 2147483647"),
+        )));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+
+        let mut interpreter = get_interpreter();
+        interpreter.set_code(code);
+        let error = interpreter.run().unwrap_err();
+        assert!(error.to_string().contains("HeapLimitError"));
+
+        // Arrays allocated in a loop are never freed
+        let mut code = CodeType::new();
+        code.push(Box::new(StartLoopCommand::new(5, String::from("LOOP 5"))));
+        code.push(Box::new(NewArrayCommand::new(
+            MAX_HEAP_ELEMENTS / 4,
+            String::from("NEW_ARRAY"),
+        )));
+        code.push(Box::new(EndLoopCommand::new(String::from("END_LOOP"))));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+
+        let mut interpreter = get_interpreter();
+        interpreter.set_code(code);
+        let error = interpreter.run().unwrap_err();
+        assert!(error.to_string().contains("HeapLimitError"));
+    }
+
+    #[test]
+    fn test_map_heap_limit() {
+        // The map and its first key take the last free elements, only that key can be set again
+        let map_put = |key: &str| {
+            let mut code = CodeType::new();
+            code.push(Box::new(NewArrayCommand::new(
+                MAX_HEAP_ELEMENTS - 2,
+                String::from("NEW_ARRAY"),
+            )));
+            code.push(Box::new(MapNewCommand::new(String::from("MAP_NEW"))));
+            code.push(Box::new(WriteVariableCommand::new(
+                String::from("map"),
+                String::from("WRITE_VAR map"),
+            )));
+            code.push(Box::new(ReadVariableCommand::new(
+                String::from("map"),
+                String::from("READ_VAR map"),
+            )));
+            code.push(Box::new(LoadValueCommand::new("a", "".to_owned())));
+            code.push(Box::new(LoadValueCommand::new(1, "".to_owned())));
+            code.push(Box::new(MapPutCommand::new(String::from("MAP_PUT"))));
+            code.push(Box::new(ReadVariableCommand::new(
+                String::from("map"),
+                String::from("READ_VAR map"),
+            )));
+            code.push(Box::new(LoadValueCommand::new(key, "".to_owned())));
+            code.push(Box::new(LoadValueCommand::new(2, "".to_owned())));
+            code.push(Box::new(MapPutCommand::new(String::from("MAP_PUT"))));
+            code.push(Box::new(ReturnCommand::new("".to_owned())));
+            let mut interpreter = get_interpreter();
+            interpreter.set_code(code);
+            interpreter.run()
+        };
+        assert!(map_put("a").is_ok());
+        let error = map_put("b").unwrap_err();
+        assert!(error.to_string().contains("HeapLimitError"));
+
+        // Maps allocated in a loop are never freed
+        let mut code = CodeType::new();
+        code.push(Box::new(NewArrayCommand::new(
+            MAX_HEAP_ELEMENTS - 4,
+            String::from("NEW_ARRAY"),
+        )));
+        code.push(Box::new(StartLoopCommand::new(5, String::from("LOOP 5"))));
+        code.push(Box::new(MapNewCommand::new(String::from("MAP_NEW"))));
+        code.push(Box::new(EndLoopCommand::new(String::from("END_LOOP"))));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+
+        let mut interpreter = get_interpreter();
+        interpreter.set_code(code);
+        let error = interpreter.run().unwrap_err();
+        assert!(error.to_string().contains("HeapLimitError"));
+    }
+
+    #[test]
+    fn test_linear_memory_commands() {
+        let mut code = CodeType::new();
+        code.push(Box::new(LoadValueCommand::new(4, "".to_owned())));
+        code.push(Box::new(LoadValueCommand::new(-42, "".to_owned())));
+        code.push(Box::new(StoreAddressCommand::new(String::from(
+            "STORE_ADDR",
+        ))));
+        code.push(Box::new(LoadValueCommand::new(4, "".to_owned())));
+        code.push(Box::new(LoadAddressCommand::new(String::from("LOAD_ADDR"))));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+
+        let state = State::with_linear_memory(None, 8);
+        let mut interpreter = ByteCode::new(state, code);
+        assert_eq!(interpreter.run().unwrap().unwrap(), Value::Int(-42));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_linear_memory_out_of_bounds_error() {
+        let mut code = CodeType::new();
+        code.push(Box::new(LoadValueCommand::new(5, "".to_owned())));
+        code.push(Box::new(LoadAddressCommand::new(String::from("LOAD_ADDR"))));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+
+        let state = State::with_linear_memory(None, 8);
+        let mut interpreter = ByteCode::new(state, code);
+        interpreter.run().unwrap();
+    }
+
+    #[test]
+    fn test_map_has_command() {
+        let mut code = CodeType::new();
+        code.push(Box::new(MapNewCommand::new(String::from("MAP_NEW"))));
+        code.push(Box::new(LoadValueCommand::new(1, "".to_owned())));
+        code.push(Box::new(MapHasCommand::new(String::from("MAP_HAS"))));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+
+        let mut interpreter = get_interpreter();
+        interpreter.set_code(code);
+        assert_eq!(interpreter.run().unwrap().unwrap(), Value::Bool(false));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_unknown_key_error() {
+        let mut code = CodeType::new();
+        code.push(Box::new(MapNewCommand::new(String::from("MAP_NEW"))));
+        code.push(Box::new(LoadValueCommand::new("missing", "".to_owned())));
+        code.push(Box::new(MapGetCommand::new(String::from("MAP_GET"))));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+
+        let mut interpreter = get_interpreter();
+        interpreter.set_code(code);
+        interpreter.run().unwrap();
+    }
+
+    #[test]
+    fn test_output_commands() {
+        let mut code = CodeType::new();
+        code.push(Box::new(LoadValueCommand::new(1.5, "".to_owned())));
+        code.push(Box::new(PrintCommand::new(String::from("PRINT"))));
+        code.push(Box::new(LoadValueCommand::new(79, "".to_owned())));
+        code.push(Box::new(EmitCommand::new(String::from("EMIT"))));
+        code.push(Box::new(LoadValueCommand::new(75, "".to_owned())));
+        code.push(Box::new(EmitCommand::new(String::from("EMIT"))));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+
+        let sink = CaptureSink::new();
+        let mut interpreter = get_interpreter();
+        interpreter.state.set_output_sink(Box::new(sink.clone()));
+        interpreter.set_code(code);
+        assert_eq!(interpreter.run().unwrap(), None);
+        assert_eq!(sink.contents(), "1.5\nOK");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_character_error() {
+        let mut code = CodeType::new();
+        code.push(Box::new(LoadValueCommand::new(-1, "".to_owned())));
+        code.push(Box::new(EmitCommand::new(String::from("EMIT"))));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+
+        let mut interpreter = get_interpreter();
+        interpreter
+            .state
+            .set_output_sink(Box::new(CaptureSink::new()));
+        interpreter.set_code(code);
+        interpreter.run().unwrap();
+    }
+
+    #[test]
+    fn test_input_command() {
+        let mut code = CodeType::new();
+        code.push(Box::new(InputCommand::new(String::from("INPUT"))));
+        code.push(Box::new(InputCommand::new(String::from("INPUT"))));
+        code.push(Box::new(SubCommand::new(String::from("SUB"))));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+
+        let mut interpreter = get_interpreter();
+        interpreter
+            .state
+            .set_input_source(Box::new(VecSource::new(vec![10, 3])));
+        interpreter.set_code(code);
+        assert_eq!(interpreter.run().unwrap().unwrap(), Value::Int(7));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_input_exhausted_error() {
+        let mut code = CodeType::new();
+        code.push(Box::new(InputCommand::new(String::from("INPUT"))));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+
+        let mut interpreter = get_interpreter();
+        interpreter
+            .state
+            .set_input_source(Box::new(VecSource::new(Vec::new())));
+        interpreter.set_code(code);
+        interpreter.run().unwrap();
+    }
+
+    #[test]
+    fn test_rand_commands_are_reproducible() {
+        let run_with_seed = |seed| {
+            let mut code = CodeType::new();
+            code.push(Box::new(RandCommand::new(String::from("RAND"))));
+            code.push(Box::new(RandRangeCommand::new(
+                1,
+                6,
+                String::from("RAND_RANGE 1 6"),
+            )));
+            code.push(Box::new(ReturnCommand::new("".to_owned())));
+            let mut interpreter = get_interpreter();
+            interpreter.state.set_seed(seed);
+            interpreter.set_code(code);
+            interpreter.run().unwrap().unwrap()
+        };
+        assert_eq!(run_with_seed(42), run_with_seed(42));
+        for seed in 0..100 {
+            assert!(matches!(run_with_seed(seed), Value::Int(1..=6)));
+        }
+    }
+
+    #[test]
+    fn test_time_command() {
+        let mut code = CodeType::new();
+        code.push(Box::new(TimeCommand::new(String::from("TIME"))));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+
+        let clock = ManualClock::new(1_700_000_000);
+        clock.advance(60);
+        let mut interpreter = get_interpreter();
+        interpreter.state.set_clock(Box::new(clock));
+        interpreter.set_code(code);
+        assert_eq!(
+            interpreter.run().unwrap().unwrap(),
+            Value::Int(1_700_000_060)
+        );
+    }
+
+    #[test]
+    fn test_time_command_after_2038() {
+        let clock = ManualClock::new(1 << 31);
+        clock.advance(5);
+        let run_with_big_int = |big_int_mode| {
+            let mut code = CodeType::new();
+            code.push(Box::new(TimeCommand::new(String::from("TIME"))));
+            code.push(Box::new(LoadValueCommand::new(1, "".to_owned())));
+            code.push(Box::new(AddCommand::new(String::from("ADD"))));
+            code.push(Box::new(ReturnCommand::new("".to_owned())));
+            let mut interpreter = get_interpreter();
+            interpreter.state.set_clock(Box::new(clock.clone()));
+            interpreter.state.set_big_int_mode(big_int_mode);
+            interpreter.set_code(code);
+            interpreter.run()
+        };
+        let error = run_with_big_int(false).unwrap_err();
+        assert!(error.to_string().contains("IntegerOverflowError"));
+        assert_eq!(
+            run_with_big_int(true).unwrap().unwrap(),
+            Value::BigInt(BigInt::from(2_147_483_654_i64))
+        );
+
+        clock.set(u64::MAX);
+        clock.advance(1);
+        assert_eq!(clock.now(), u64::MAX);
+    }
+
+    #[test]
+    fn test_assert_commands() {
+        let mut code = CodeType::new();
+        code.push(Box::new(LoadValueCommand::new(3, "".to_owned())));
+        code.push(Box::new(AssertCommand::new(String::from("ASSERT"))));
+        code.push(Box::new(LoadValueCommand::new("a", "".to_owned())));
+        code.push(Box::new(LoadValueCommand::new("a", "".to_owned())));
+        code.push(Box::new(AssertEqCommand::new(String::from("ASSERT_EQ"))));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+
+        let mut interpreter = get_interpreter();
+        interpreter.set_code(code);
+        assert_eq!(interpreter.run().unwrap(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_assertion_failed_error() {
+        let mut code = CodeType::new();
+        code.push(Box::new(LoadValueCommand::new(1, "".to_owned())));
+        code.push(Box::new(LoadValueCommand::new(1.0, "".to_owned())));
+        code.push(Box::new(AssertEqCommand::new(String::from("ASSERT_EQ"))));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+
+        let mut interpreter = get_interpreter();
+        interpreter.set_code(code);
+        interpreter.run().unwrap();
+    }
+
+    #[test]
+    fn test_call_native_command() {
+        let mut code = CodeType::new();
+        code.push(Box::new(LoadValueCommand::new(7, "".to_owned())));
+        code.push(Box::new(LoadValueCommand::new(2, "".to_owned())));
+        code.push(Box::new(CallNativeCommand::new(
+            String::from("divmod"),
+            String::from("CALL_NATIVE divmod"),
+        )));
+        code.push(Box::new(SubCommand::new(String::from("SUB"))));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+
+        let mut interpreter = get_interpreter();
+        interpreter.register_native("divmod", 2, |arguments| match arguments {
+            [Value::Int(first), Value::Int(second)] => {
+                Ok(vec![Value::Int(first / second), Value::Int(first % second)])
+            }
+            _ => Err(String::from("divmod expects two integers")),
+        });
+        interpreter.set_code(code);
+        assert_eq!(interpreter.run().unwrap().unwrap(), Value::Int(2));
+    }
+
+    #[test]
+    fn test_unknown_native_function_error() {
+        let mut code = CodeType::new();
+        code.push(Box::new(LoadValueCommand::new(1, "".to_owned())));
+        code.push(Box::new(PrintCommand::new(String::from("PRINT"))));
+        code.push(Box::new(CallNativeCommand::new(
+            String::from("missing"),
+            String::from("CALL_NATIVE missing"),
+        )));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+
+        let sink = CaptureSink::new();
+        let mut interpreter = get_interpreter();
+        interpreter.state.set_output_sink(Box::new(sink.clone()));
+        interpreter.set_code(code);
+        assert!(interpreter.link().is_err());
+        assert!(interpreter.run().is_err());
+        assert_eq!(sink.contents(), "");
     }
 
     #[test]
@@ -110,7 +702,7 @@ mod tests {
         code.push(Box::new(ReturnCommand::new("".to_owned())));
         let mut interpreter = get_interpreter();
         interpreter.set_code(code);
-        assert_eq!(interpreter.run().unwrap().unwrap(), 42);
+        assert_eq!(interpreter.run().unwrap().unwrap(), Value::Int(42));
     }
 
     #[test]
@@ -128,7 +720,7 @@ mod tests {
         code.push(Box::new(ReturnCommand::new("".to_owned())));
         let mut interpreter = get_interpreter();
         interpreter.set_code(code);
-        assert_eq!(interpreter.run().unwrap().unwrap(), 42);
+        assert_eq!(interpreter.run().unwrap().unwrap(), Value::Int(42));
     }
 
     #[test]
@@ -143,7 +735,73 @@ mod tests {
 
         let mut interpreter = get_interpreter();
         interpreter.set_code(code);
-        assert_eq!(interpreter.run().unwrap().unwrap(), 6);
+        assert_eq!(interpreter.run().unwrap().unwrap(), Value::Int(6));
+    }
+
+    #[test]
+    fn test_return_multiple_values() {
+        let mut code = CodeType::new();
+        code.push(Box::new(LoadValueCommand::new(1, "".to_owned())));
+        code.push(Box::new(LoadValueCommand::new(2, "".to_owned())));
+        code.push(Box::new(LoadValueCommand::new("three", "".to_owned())));
+        code.push(Box::new(ReturnCommand::with_count(
+            2,
+            String::from("RETURN 2"),
+        )));
+
+        let mut interpreter = get_interpreter();
+        interpreter.set_code(code);
+        assert_eq!(
+            interpreter.run_values().unwrap(),
+            vec![Value::Int(2), Value::from("three")]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_return_multiple_values_empty_stack_error() {
+        let mut code = CodeType::new();
+        code.push(Box::new(LoadValueCommand::new(1, "".to_owned())));
+        code.push(Box::new(ReturnCommand::with_count(
+            2,
+            String::from("RETURN 2"),
+        )));
+
+        let mut interpreter = get_interpreter();
+        interpreter.set_code(code);
+        interpreter.run_values().unwrap();
+    }
+
+    #[test]
+    fn test_debug_commands() {
+        let mut code = CodeType::new();
+        code.push(Box::new(LoadValueCommand::new(1, "".to_owned())));
+        code.push(Box::new(WriteVariableCommand::new(
+            String::from("x"),
+            String::from("WRITE_VAR x"),
+        )));
+        code.push(Box::new(LoadValueCommand::new(2, "".to_owned())));
+        code.push(Box::new(DumpCommand::new(String::from("5:     DUMP"))));
+        code.push(Box::new(BreakpointCommand::new(String::from(
+            "6:     BREAKPOINT",
+        ))));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+
+        let sink = CaptureSink::new();
+        let mut interpreter = get_interpreter();
+        interpreter.state.set_debug_sink(Box::new(sink.clone()));
+        interpreter.set_breakpoint_handler(Box::new(|state, line| {
+            assert_eq!(line, "6:     BREAKPOINT");
+            assert_eq!(state.get_stack(), &[Value::Int(2)]);
+            state.push_to_stack(Value::Int(3));
+        }));
+        interpreter.set_code(code);
+        assert_eq!(interpreter.run().unwrap().unwrap(), Value::Int(3));
+        assert_eq!(
+            sink.contents(),
+            "DUMP LINE => 5:     DUMP\nstack: [Int(2)]\nmemory: {x = Int(1)}\n\
+             loop: SimpleLoop { start_pointer: None, counter: None }\n"
+        );
     }
 
     #[test]
@@ -153,7 +811,7 @@ mod tests {
         code.push(Box::new(LoadValueCommand::new(1, "".to_owned())));
         let mut interpreter = get_interpreter();
         interpreter.set_code(code);
-        assert_eq!(interpreter.run().unwrap().unwrap(), 6);
+        assert_eq!(interpreter.run().unwrap().unwrap(), Value::Int(6));
     }
 
     #[test]
@@ -166,7 +824,7 @@ mod tests {
 
         let mut interpreter = get_interpreter();
         interpreter.set_code(code);
-        assert_eq!(interpreter.run().unwrap().unwrap(), 2);
+        assert_eq!(interpreter.run().unwrap().unwrap(), Value::Int(2));
     }
 
     #[test]
@@ -179,7 +837,88 @@ mod tests {
         code.push(Box::new(ReturnCommand::new("".to_owned())));
         let mut interpreter = get_interpreter();
         interpreter.set_code(code);
-        assert_eq!(interpreter.run().unwrap().unwrap(), 2);
+        assert_eq!(interpreter.run().unwrap().unwrap(), Value::Int(2));
+    }
+
+    #[test]
+    fn test_big_int_mode_overflow() {
+        let mut code = CodeType::new();
+        code.push(Box::new(LoadValueCommand::new(i32::MAX, "".to_owned())));
+        code.push(Box::new(LoadValueCommand::new(i32::MAX, "".to_owned())));
+        code.push(Box::new(AddCommand::new(String::from("ADD"))));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+        let mut interpreter = get_interpreter();
+        interpreter.state.set_big_int_mode(true);
+        interpreter.set_code(code);
+        assert_eq!(
+            interpreter.run().unwrap().unwrap().to_string(),
+            "4294967294"
+        );
+    }
+
+    #[test]
+    fn test_big_int_mode_small_results_are_int() {
+        let mut code = CodeType::new();
+        code.push(Box::new(NewArrayCommand::new(
+            3,
+            String::from("NEW_ARRAY 3"),
+        )));
+        code.push(Box::new(LoadValueCommand::new(1, "".to_owned())));
+        code.push(Box::new(LoadValueCommand::new(1, "".to_owned())));
+        code.push(Box::new(AddCommand::new(String::from("ADD"))));
+        code.push(Box::new(ArrayGetCommand::new(String::from("ARR_GET"))));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+        let mut interpreter = get_interpreter();
+        interpreter.state.set_big_int_mode(true);
+        interpreter.set_code(code);
+        assert_eq!(interpreter.run().unwrap().unwrap(), Value::Int(0));
+    }
+
+    #[test]
+    fn test_variable_lifecycle_commands() {
+        let mut code = CodeType::new();
+        code.push(Box::new(LoadValueCommand::new(1, "".to_owned())));
+        code.push(Box::new(WriteVariableCommand::new(
+            String::from("x"),
+            String::from("WRITE_VAR x"),
+        )));
+        code.push(Box::new(HasVariableCommand::new(
+            String::from("x"),
+            String::from("HAS_VAR x"),
+        )));
+        code.push(Box::new(DeleteVariableCommand::new(
+            String::from("x"),
+            String::from("DEL_VAR x"),
+        )));
+        code.push(Box::new(HasVariableCommand::new(
+            String::from("x"),
+            String::from("HAS_VAR x"),
+        )));
+        code.push(Box::new(SubCommand::new(String::from("SUB"))));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+        let mut interpreter = get_interpreter();
+        interpreter.set_code(code);
+        assert_eq!(interpreter.run().unwrap().unwrap(), Value::Int(1));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_const_reassignment_error() {
+        let mut code = CodeType::new();
+        code.push(Box::new(LoadValueCommand::new(1, "".to_owned())));
+        code.push(Box::new(ConstVariableCommand::new(
+            String::from("RATE"),
+            String::from("CONST_VAR RATE"),
+        )));
+        code.push(Box::new(LoadValueCommand::new(2, "".to_owned())));
+        code.push(Box::new(WriteVariableCommand::new(
+            String::from("RATE"),
+            String::from("WRITE_VAR RATE"),
+        )));
+        code.push(Box::new(ReturnCommand::new("".to_owned())));
+        let mut interpreter = get_interpreter();
+        interpreter.set_code(code);
+        interpreter.run().unwrap();
     }
 
     #[test]
@@ -193,7 +932,7 @@ mod tests {
         code.push(Box::new(ReturnCommand::new("".to_owned())));
         let mut interpreter = get_interpreter();
         interpreter.set_code(code);
-        assert_eq!(interpreter.run().unwrap().unwrap(), 42);
+        assert_eq!(interpreter.run().unwrap().unwrap(), Value::Int(42));
     }
 
     #[test]
@@ -207,7 +946,7 @@ mod tests {
         code.push(Box::new(ReturnCommand::new("".to_owned())));
         let mut interpreter = get_interpreter();
         interpreter.set_code(code);
-        assert_eq!(interpreter.run().unwrap().unwrap(), 6);
+        assert_eq!(interpreter.run().unwrap().unwrap(), Value::Int(6));
     }
 
     #[test]
@@ -225,7 +964,7 @@ mod tests {
 
         let mut interpreter = get_interpreter();
         interpreter.set_code(code);
-        assert_eq!(interpreter.run().unwrap().unwrap(), 6);
+        assert_eq!(interpreter.run().unwrap().unwrap(), Value::Int(6));
     }
 
     #[test]
@@ -241,13 +980,13 @@ mod tests {
         code.push(Box::new(ReturnCommand::new("".to_owned())));
         let state: State = State::new(None);
         let mut interpreter = ByteCode::new(state, code);
-        assert_eq!(interpreter.run().unwrap().unwrap(), 6);
+        assert_eq!(interpreter.run().unwrap().unwrap(), Value::Int(6));
     }
 
     #[test]
     #[should_panic]
     fn test_no_code_error() {
         let mut interpreter = get_interpreter();
-        assert_eq!(interpreter.run().unwrap().unwrap(), 6);
+        assert_eq!(interpreter.run().unwrap().unwrap(), Value::Int(6));
     }
 }
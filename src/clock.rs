@@ -0,0 +1,49 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Source of the current time used by the `TIME` instruction.
+pub trait Clock {
+    /// Seconds elapsed since the Unix epoch.
+    fn now(&self) -> u64;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0)
+    }
+}
+
+/// Clock which only moves when told to. Clones share the same time, so a
+/// clone can be handed to `State` and advanced from a test.
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock {
+    seconds: Rc<Cell<u64>>,
+}
+
+impl ManualClock {
+    pub fn new(seconds: u64) -> Self {
+        ManualClock {
+            seconds: Rc::new(Cell::new(seconds)),
+        }
+    }
+
+    pub fn set(&self, seconds: u64) {
+        self.seconds.set(seconds);
+    }
+
+    pub fn advance(&self, seconds: u64) {
+        self.seconds.set(self.seconds.get().saturating_add(seconds));
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> u64 {
+        self.seconds.get()
+    }
+}
@@ -1,120 +1,1273 @@
+use crate::bigint::BigInt;
+use crate::decimal::{Decimal, RoundingMode};
 use crate::errors::*;
+use crate::heap::{HeapRef, MapKey};
+use crate::sandbox::SandboxError;
+use crate::span::Span;
 use crate::state::State;
+use crate::value::Value;
 pub trait Command: std::fmt::Debug {
     fn execute(&self, state: &mut State) -> Result<(), RuntimeError>;
+
+    /// Checks references to the host environment before the program runs.
+    fn link(&self, _state: &State) -> Result<(), LinkError> {
+        Ok(())
+    }
+}
+
+/// Wraps a command with its location in the source, so runtime errors of
+/// the command point at the instruction which raised them.
+#[derive(Debug)]
+pub struct SpannedCommand {
+    command: Box<dyn Command>,
+    span: Span,
+}
+
+impl SpannedCommand {
+    pub fn new(command: Box<dyn Command>, span: Span) -> Self {
+        SpannedCommand { command, span }
+    }
+}
+
+impl Command for SpannedCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        self.command
+            .execute(state)
+            .map_err(|error| error.with_span(self.span.clone()))
+    }
+
+    fn link(&self, state: &State) -> Result<(), LinkError> {
+        self.command
+            .link(state)
+            .map_err(|error| error.with_span(self.span.clone()))
+    }
+}
+
+fn pop_int(state: &mut State, line: &str) -> Result<i32, RuntimeError> {
+    match state.pop_from_stack(line)? {
+        Value::Int(value) => Ok(value),
+        other => Err(TypeMismatchError::new("Int", other.type_name(), line))?,
+    }
+}
+
+fn pop_big_int(state: &mut State, line: &str) -> Result<BigInt, RuntimeError> {
+    match state.pop_from_stack(line)? {
+        Value::Int(value) => Ok(BigInt::from(value)),
+        Value::BigInt(value) => Ok(value),
+        other => Err(TypeMismatchError::new("BigInt", other.type_name(), line))?,
+    }
+}
+
+fn pop_decimal(state: &mut State, line: &str) -> Result<Decimal, RuntimeError> {
+    match state.pop_from_stack(line)? {
+        Value::Decimal(value) => Ok(value),
+        other => Err(TypeMismatchError::new("Decimal", other.type_name(), line))?,
+    }
+}
+
+fn pop_str(state: &mut State, line: &str) -> Result<String, RuntimeError> {
+    match state.pop_from_stack(line)? {
+        Value::Str(value) => Ok(value),
+        other => Err(TypeMismatchError::new("Str", other.type_name(), line))?,
+    }
+}
+
+fn pop_array(state: &mut State, line: &str) -> Result<HeapRef, RuntimeError> {
+    match state.pop_from_stack(line)? {
+        Value::Array(handle) => Ok(handle),
+        other => Err(TypeMismatchError::new("Array", other.type_name(), line))?,
+    }
+}
+
+fn pop_map(state: &mut State, line: &str) -> Result<HeapRef, RuntimeError> {
+    match state.pop_from_stack(line)? {
+        Value::Map(handle) => Ok(handle),
+        other => Err(TypeMismatchError::new("Map", other.type_name(), line))?,
+    }
+}
+
+fn pop_map_key(state: &mut State, line: &str) -> Result<MapKey, RuntimeError> {
+    let value = state.pop_from_stack(line)?;
+    let key = MapKey::try_from(value)
+        .map_err(|value| TypeMismatchError::new("map key", value.type_name(), line))?;
+    Ok(key)
+}
+
+/// Converts `index` into a position inside an array of `length` elements.
+fn check_index(index: i32, length: usize, line: &str) -> Result<usize, RuntimeError> {
+    match usize::try_from(index) {
+        Ok(position) if position < length => Ok(position),
+        _ => Err(IndexOutOfBoundsError::new(index, length, line))?,
+    }
+}
+
+fn pop_float(state: &mut State, line: &str) -> Result<f64, RuntimeError> {
+    match state.pop_from_stack(line)? {
+        Value::Float(value) => Ok(value),
+        other => Err(TypeMismatchError::new("Float", other.type_name(), line))?,
+    }
+}
+
+fn execute_int_operation(
+    state: &mut State,
+    line: &str,
+    operation: fn(i32, i32) -> Option<i32>,
+    big_operation: fn(&BigInt, &BigInt) -> Option<BigInt>,
+) -> Result<(), RuntimeError> {
+    if state.is_big_int_mode() {
+        let second = pop_big_int(state, line)?;
+        let first = pop_big_int(state, line)?;
+        let result = big_operation(&first, &second).ok_or(IntegerOverflowError::new(line))?;
+        state.push_to_stack(Value::from(result));
+        state.inc_command_pointer();
+        return Ok(());
+    }
+    let second = pop_int(state, line)?;
+    let first = pop_int(state, line)?;
+    let result = operation(first, second).ok_or(IntegerOverflowError::new(line))?;
+    state.push_to_stack(Value::Int(result));
+    state.inc_command_pointer();
+    Ok(())
+}
+
+fn execute_float_operation(
+    state: &mut State,
+    line: &str,
+    operation: fn(f64, f64) -> f64,
+) -> Result<(), RuntimeError> {
+    let second = pop_float(state, line)?;
+    let first = pop_float(state, line)?;
+    state.push_to_stack(Value::Float(operation(first, second)));
+    state.inc_command_pointer();
+    Ok(())
+}
+
+#[derive(Debug)]
+pub struct LoadValueCommand {
+    value: Value,
+    line: String,
+}
+
+impl LoadValueCommand {
+    pub fn new<V: Into<Value>>(value: V, line: String) -> Self {
+        LoadValueCommand {
+            value: value.into(),
+            line,
+        }
+    }
+}
+
+impl Command for LoadValueCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        if let Value::BigInt(_) = self.value {
+            if !state.is_big_int_mode() {
+                return Err(IntegerOverflowError::new(&self.line))?;
+            }
+        }
+        state.push_to_stack(self.value.clone());
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct AddCommand {
+    line: String,
+}
+
+impl AddCommand {
+    pub fn new(line: String) -> Self {
+        AddCommand { line }
+    }
+}
+
+impl Command for AddCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        execute_int_operation(state, &self.line, i32::checked_add, |first, second| {
+            Some(first + second)
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct SubCommand {
+    line: String,
+}
+
+impl SubCommand {
+    pub fn new(line: String) -> Self {
+        SubCommand { line }
+    }
+}
+
+impl Command for SubCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        execute_int_operation(state, &self.line, i32::checked_sub, |first, second| {
+            Some(first - second)
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct MulCommand {
+    line: String,
+}
+
+impl MulCommand {
+    pub fn new(line: String) -> Self {
+        MulCommand { line }
+    }
+}
+
+impl Command for MulCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        execute_int_operation(state, &self.line, i32::checked_mul, |first, second| {
+            Some(first * second)
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct DivCommand {
+    line: String,
+}
+
+impl DivCommand {
+    pub fn new(line: String) -> Self {
+        DivCommand { line }
+    }
+}
+
+impl Command for DivCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        execute_int_operation(state, &self.line, i32::checked_div, BigInt::checked_div)
+    }
+}
+
+#[derive(Debug)]
+pub struct FloatAddCommand {
+    line: String,
+}
+
+impl FloatAddCommand {
+    pub fn new(line: String) -> Self {
+        FloatAddCommand { line }
+    }
+}
+
+impl Command for FloatAddCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        execute_float_operation(state, &self.line, |first, second| first + second)
+    }
+}
+
+#[derive(Debug)]
+pub struct FloatSubCommand {
+    line: String,
+}
+
+impl FloatSubCommand {
+    pub fn new(line: String) -> Self {
+        FloatSubCommand { line }
+    }
+}
+
+impl Command for FloatSubCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        execute_float_operation(state, &self.line, |first, second| first - second)
+    }
+}
+
+#[derive(Debug)]
+pub struct FloatMulCommand {
+    line: String,
+}
+
+impl FloatMulCommand {
+    pub fn new(line: String) -> Self {
+        FloatMulCommand { line }
+    }
+}
+
+impl Command for FloatMulCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        execute_float_operation(state, &self.line, |first, second| first * second)
+    }
+}
+
+#[derive(Debug)]
+pub struct FloatDivCommand {
+    line: String,
+}
+
+impl FloatDivCommand {
+    pub fn new(line: String) -> Self {
+        FloatDivCommand { line }
+    }
+}
+
+impl Command for FloatDivCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        execute_float_operation(state, &self.line, |first, second| first / second)
+    }
+}
+
+#[derive(Debug)]
+pub struct FloatSqrtCommand {
+    line: String,
+}
+
+impl FloatSqrtCommand {
+    pub fn new(line: String) -> Self {
+        FloatSqrtCommand { line }
+    }
+}
+
+impl Command for FloatSqrtCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let value = pop_float(state, &self.line)?;
+        state.push_to_stack(Value::Float(value.sqrt()));
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct IntToFloatCommand {
+    line: String,
+}
+
+impl IntToFloatCommand {
+    pub fn new(line: String) -> Self {
+        IntToFloatCommand { line }
+    }
+}
+
+impl Command for IntToFloatCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let value = pop_int(state, &self.line)?;
+        state.push_to_stack(Value::Float(value as f64));
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct FloatToIntCommand {
+    line: String,
+}
+
+impl FloatToIntCommand {
+    pub fn new(line: String) -> Self {
+        FloatToIntCommand { line }
+    }
+}
+
+impl Command for FloatToIntCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let value = pop_float(state, &self.line)?.trunc();
+        if !value.is_finite() || value < i32::MIN as f64 || value > i32::MAX as f64 {
+            return Err(IntegerOverflowError::new(&self.line))?;
+        }
+        state.push_to_stack(Value::Int(value as i32));
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+fn execute_decimal_operation(
+    state: &mut State,
+    line: &str,
+    rounding_mode: Option<RoundingMode>,
+    operation: fn(&Decimal, &Decimal, u32, RoundingMode) -> Option<Decimal>,
+) -> Result<(), RuntimeError> {
+    let second = pop_decimal(state, line)?;
+    let first = pop_decimal(state, line)?;
+    let scale = state.get_decimal_scale();
+    let rounding_mode = rounding_mode.unwrap_or(state.get_rounding_mode());
+    let result =
+        operation(&first, &second, scale, rounding_mode).ok_or(DecimalOverflowError::new(line))?;
+    state.push_to_stack(Value::Decimal(result));
+    state.inc_command_pointer();
+    Ok(())
+}
+
+#[derive(Debug)]
+pub struct LoadDecimalCommand {
+    value: Decimal,
+    line: String,
+}
+
+impl LoadDecimalCommand {
+    pub fn new(value: Decimal, line: String) -> Self {
+        LoadDecimalCommand { value, line }
+    }
+}
+
+impl Command for LoadDecimalCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let value = self
+            .value
+            .rescale(state.get_decimal_scale(), state.get_rounding_mode())
+            .ok_or(DecimalOverflowError::new(&self.line))?;
+        state.push_to_stack(Value::Decimal(value));
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct DecimalAddCommand {
+    line: String,
+}
+
+impl DecimalAddCommand {
+    pub fn new(line: String) -> Self {
+        DecimalAddCommand { line }
+    }
+}
+
+impl Command for DecimalAddCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        execute_decimal_operation(state, &self.line, None, |first, second, scale, mode| {
+            first.checked_add(second)?.rescale(scale, mode)
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct DecimalSubCommand {
+    line: String,
+}
+
+impl DecimalSubCommand {
+    pub fn new(line: String) -> Self {
+        DecimalSubCommand { line }
+    }
+}
+
+impl Command for DecimalSubCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        execute_decimal_operation(state, &self.line, None, |first, second, scale, mode| {
+            first.checked_sub(second)?.rescale(scale, mode)
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct DecimalMulCommand {
+    rounding_mode: Option<RoundingMode>,
+    line: String,
+}
+
+impl DecimalMulCommand {
+    /// Without `rounding_mode` the rounding mode of the `State` is used.
+    pub fn new(rounding_mode: Option<RoundingMode>, line: String) -> Self {
+        DecimalMulCommand {
+            rounding_mode,
+            line,
+        }
+    }
+}
+
+impl Command for DecimalMulCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        execute_decimal_operation(state, &self.line, self.rounding_mode, Decimal::checked_mul)
+    }
+}
+
+#[derive(Debug)]
+pub struct DecimalDivCommand {
+    rounding_mode: Option<RoundingMode>,
+    line: String,
+}
+
+impl DecimalDivCommand {
+    /// Without `rounding_mode` the rounding mode of the `State` is used.
+    pub fn new(rounding_mode: Option<RoundingMode>, line: String) -> Self {
+        DecimalDivCommand {
+            rounding_mode,
+            line,
+        }
+    }
+}
+
+impl Command for DecimalDivCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        execute_decimal_operation(state, &self.line, self.rounding_mode, Decimal::checked_div)
+    }
+}
+
+#[derive(Debug)]
+pub struct ConcatCommand {
+    line: String,
+}
+
+impl ConcatCommand {
+    pub fn new(line: String) -> Self {
+        ConcatCommand { line }
+    }
+}
+
+impl Command for ConcatCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let second = pop_str(state, &self.line)?;
+        let mut first = pop_str(state, &self.line)?;
+        first.push_str(&second);
+        state.push_to_stack(Value::Str(first));
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct LenCommand {
+    line: String,
+}
+
+impl LenCommand {
+    pub fn new(line: String) -> Self {
+        LenCommand { line }
+    }
+}
+
+impl Command for LenCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let value = pop_str(state, &self.line)?;
+        let length = i32::try_from(value.chars().count())
+            .map_err(|_| IntegerOverflowError::new(&self.line))?;
+        state.push_to_stack(Value::Int(length));
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+/// Takes `length` characters starting at `start`, where `length` is on the
+/// top of the stack. The range is clamped to the bounds of the string.
+#[derive(Debug)]
+pub struct SubstrCommand {
+    line: String,
+}
+
+impl SubstrCommand {
+    pub fn new(line: String) -> Self {
+        SubstrCommand { line }
+    }
+}
+
+impl Command for SubstrCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let length = pop_int(state, &self.line)?.max(0) as usize;
+        let start = pop_int(state, &self.line)?.max(0) as usize;
+        let value = pop_str(state, &self.line)?;
+        let substring: String = value.chars().skip(start).take(length).collect();
+        state.push_to_stack(Value::Str(substring));
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct StrEqCommand {
+    line: String,
+}
+
+impl StrEqCommand {
+    pub fn new(line: String) -> Self {
+        StrEqCommand { line }
+    }
+}
+
+impl Command for StrEqCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let second = pop_str(state, &self.line)?;
+        let first = pop_str(state, &self.line)?;
+        state.push_to_stack(Value::Bool(first == second));
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct ToStrCommand {
+    line: String,
+}
+
+impl ToStrCommand {
+    pub fn new(line: String) -> Self {
+        ToStrCommand { line }
+    }
+}
+
+impl Command for ToStrCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let value = state.pop_from_stack(&self.line)?;
+        state.push_to_stack(Value::Str(value.to_string()));
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseIntCommand {
+    line: String,
+}
+
+impl ParseIntCommand {
+    pub fn new(line: String) -> Self {
+        ParseIntCommand { line }
+    }
+}
+
+impl Command for ParseIntCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let value = pop_str(state, &self.line)?;
+        let value: i32 = value
+            .trim()
+            .parse()
+            .map_err(|_| StringParseError::new(&self.line))?;
+        state.push_to_stack(Value::Int(value));
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct NewArrayCommand {
+    size: usize,
+    line: String,
+}
+
+impl NewArrayCommand {
+    pub fn new(size: usize, line: String) -> Self {
+        NewArrayCommand { size, line }
+    }
+}
+
+impl Command for NewArrayCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let handle = state.heap_allocate_array(self.size, &self.line)?;
+        state.push_to_stack(Value::Array(handle));
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct ArrayGetCommand {
+    line: String,
+}
+
+impl ArrayGetCommand {
+    pub fn new(line: String) -> Self {
+        ArrayGetCommand { line }
+    }
+}
+
+impl Command for ArrayGetCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let index = pop_int(state, &self.line)?;
+        let handle = pop_array(state, &self.line)?;
+        let array = state.heap_get_array_mut(handle, &self.line)?;
+        let position = check_index(index, array.len(), &self.line)?;
+        let value = array[position].clone();
+        state.push_to_stack(value);
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct ArraySetCommand {
+    line: String,
+}
+
+impl ArraySetCommand {
+    pub fn new(line: String) -> Self {
+        ArraySetCommand { line }
+    }
+}
+
+impl Command for ArraySetCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let value = state.pop_from_stack(&self.line)?;
+        let index = pop_int(state, &self.line)?;
+        let handle = pop_array(state, &self.line)?;
+        let array = state.heap_get_array_mut(handle, &self.line)?;
+        let position = check_index(index, array.len(), &self.line)?;
+        array[position] = value;
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct ArrayLenCommand {
+    line: String,
+}
+
+impl ArrayLenCommand {
+    pub fn new(line: String) -> Self {
+        ArrayLenCommand { line }
+    }
+}
+
+impl Command for ArrayLenCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let handle = pop_array(state, &self.line)?;
+        let length = state.heap_get_array_mut(handle, &self.line)?.len();
+        let length = i32::try_from(length).map_err(|_| IntegerOverflowError::new(&self.line))?;
+        state.push_to_stack(Value::Int(length));
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct ArrayPushCommand {
+    line: String,
+}
+
+impl ArrayPushCommand {
+    pub fn new(line: String) -> Self {
+        ArrayPushCommand { line }
+    }
+}
+
+impl Command for ArrayPushCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let value = state.pop_from_stack(&self.line)?;
+        let handle = pop_array(state, &self.line)?;
+        state.heap_reserve_elements(1, &self.line)?;
+        state.heap_get_array_mut(handle, &self.line)?.push(value);
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct LoadAddressCommand {
+    line: String,
+}
+
+impl LoadAddressCommand {
+    pub fn new(line: String) -> Self {
+        LoadAddressCommand { line }
+    }
+}
+
+impl Command for LoadAddressCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let address = pop_int(state, &self.line)?;
+        let value = state.linear_memory_load(address, &self.line)?;
+        state.push_to_stack(Value::Int(value));
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct StoreAddressCommand {
+    line: String,
+}
+
+impl StoreAddressCommand {
+    pub fn new(line: String) -> Self {
+        StoreAddressCommand { line }
+    }
+}
+
+impl Command for StoreAddressCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let value = pop_int(state, &self.line)?;
+        let address = pop_int(state, &self.line)?;
+        state.linear_memory_store(address, value, &self.line)?;
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct MapNewCommand {
+    line: String,
+}
+
+impl MapNewCommand {
+    pub fn new(line: String) -> Self {
+        MapNewCommand { line }
+    }
+}
+
+impl Command for MapNewCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let handle = state.heap_allocate_map(&self.line)?;
+        state.push_to_stack(Value::Map(handle));
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct MapPutCommand {
+    line: String,
+}
+
+impl MapPutCommand {
+    pub fn new(line: String) -> Self {
+        MapPutCommand { line }
+    }
+}
+
+impl Command for MapPutCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let value = state.pop_from_stack(&self.line)?;
+        let key = pop_map_key(state, &self.line)?;
+        let handle = pop_map(state, &self.line)?;
+        // Replacing the value of an existing key takes no more room
+        if !state
+            .heap_get_map_mut(handle, &self.line)?
+            .contains_key(&key)
+        {
+            state.heap_reserve_elements(1, &self.line)?;
+        }
+        state
+            .heap_get_map_mut(handle, &self.line)?
+            .insert(key, value);
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct MapGetCommand {
+    line: String,
+}
+
+impl MapGetCommand {
+    pub fn new(line: String) -> Self {
+        MapGetCommand { line }
+    }
+}
+
+impl Command for MapGetCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let key = pop_map_key(state, &self.line)?;
+        let handle = pop_map(state, &self.line)?;
+        let value = state
+            .heap_get_map_mut(handle, &self.line)?
+            .get(&key)
+            .cloned()
+            .ok_or(UnknownKeyError::new(&self.line))?;
+        state.push_to_stack(value);
+        state.inc_command_pointer();
+        Ok(())
+    }
 }
 
 #[derive(Debug)]
-pub struct LoadValueCommand {
-    value: i32,
+pub struct MapHasCommand {
+    line: String,
+}
+
+impl MapHasCommand {
+    pub fn new(line: String) -> Self {
+        MapHasCommand { line }
+    }
+}
+
+impl Command for MapHasCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let key = pop_map_key(state, &self.line)?;
+        let handle = pop_map(state, &self.line)?;
+        let contains = state
+            .heap_get_map_mut(handle, &self.line)?
+            .contains_key(&key);
+        state.push_to_stack(Value::Bool(contains));
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct MapDelCommand {
+    line: String,
+}
+
+impl MapDelCommand {
+    pub fn new(line: String) -> Self {
+        MapDelCommand { line }
+    }
+}
+
+impl Command for MapDelCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let key = pop_map_key(state, &self.line)?;
+        let handle = pop_map(state, &self.line)?;
+        state
+            .heap_get_map_mut(handle, &self.line)?
+            .remove(&key)
+            .ok_or(UnknownKeyError::new(&self.line))?;
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct PrintCommand {
+    line: String,
+}
+
+impl PrintCommand {
+    pub fn new(line: String) -> Self {
+        PrintCommand { line }
+    }
+}
+
+impl Command for PrintCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let value = state.pop_from_stack(&self.line)?;
+        state.write_output(&format!("{}\n", value), &self.line)?;
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct EmitCommand {
+    line: String,
+}
+
+impl EmitCommand {
+    pub fn new(line: String) -> Self {
+        EmitCommand { line }
+    }
+}
+
+impl Command for EmitCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let code = pop_int(state, &self.line)?;
+        let character = u32::try_from(code)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or(InvalidCharacterError::new(code, &self.line))?;
+        state.write_output(character.encode_utf8(&mut [0; 4]), &self.line)?;
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct InputCommand {
+    line: String,
+}
+
+impl InputCommand {
+    pub fn new(line: String) -> Self {
+        InputCommand { line }
+    }
+}
+
+impl Command for InputCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let value = state.read_input(&self.line)?;
+        state.push_to_stack(Value::Int(value));
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct RandCommand {
     _line: String,
 }
 
-impl LoadValueCommand {
-    pub fn new(value: i32, _line: String) -> Self {
-        LoadValueCommand { value, _line }
+impl RandCommand {
+    pub fn new(_line: String) -> Self {
+        RandCommand { _line }
     }
 }
 
-impl Command for LoadValueCommand {
+impl Command for RandCommand {
     fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
-        state.push_to_stack(self.value);
+        let value = state.get_rng().next_i32();
+        state.push_to_stack(Value::Int(value));
         state.inc_command_pointer();
         Ok(())
     }
 }
 
 #[derive(Debug)]
-pub struct AddCommand {
+pub struct RandRangeCommand {
+    low: i32,
+    high: i32,
+    _line: String,
+}
+
+impl RandRangeCommand {
+    /// Both bounds are inclusive, `low` must not be greater than `high`.
+    pub fn new(low: i32, high: i32, _line: String) -> Self {
+        RandRangeCommand { low, high, _line }
+    }
+}
+
+impl Command for RandRangeCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let value = state.get_rng().range(self.low, self.high);
+        state.push_to_stack(Value::Int(value));
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct TimeCommand {
     line: String,
 }
 
-impl AddCommand {
+impl TimeCommand {
     pub fn new(line: String) -> Self {
-        AddCommand { line }
+        TimeCommand { line }
     }
 }
 
-impl Command for AddCommand {
+impl Command for TimeCommand {
     fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
-        let second = state.pop_from_stack(&self.line)?;
-        let first = state.pop_from_stack(&self.line)?;
-        let result = first
-            .checked_add(second)
-            .ok_or(IntegerOverflowError::new(&self.line))?;
-        state.push_to_stack(result);
+        let seconds = state.get_clock().now();
+        // Times after 2038 do not fit into i32, only BigInt can hold them
+        let value = if state.is_big_int_mode() {
+            Value::from(BigInt::from(seconds))
+        } else {
+            let seconds =
+                i32::try_from(seconds).map_err(|_| IntegerOverflowError::new(&self.line))?;
+            Value::Int(seconds)
+        };
+        state.push_to_stack(value);
         state.inc_command_pointer();
         Ok(())
     }
 }
 
+/// Discards operands, replaces assertions removed with `--strip-asserts`
+/// so the stack stays the same as with the assertions.
 #[derive(Debug)]
-pub struct SubCommand {
+pub struct DropCommand {
+    count: usize,
     line: String,
 }
 
-impl SubCommand {
+impl DropCommand {
+    pub fn new(count: usize, line: String) -> Self {
+        DropCommand { count, line }
+    }
+}
+
+impl Command for DropCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        state.pop_many_from_stack(self.count, &self.line)?;
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct AssertCommand {
+    line: String,
+}
+
+impl AssertCommand {
     pub fn new(line: String) -> Self {
-        SubCommand { line }
+        AssertCommand { line }
     }
 }
 
-impl Command for SubCommand {
+impl Command for AssertCommand {
     fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
-        let second = state.pop_from_stack(&self.line)?;
-        let first = state.pop_from_stack(&self.line)?;
-        let result = first
-            .checked_sub(second)
-            .ok_or(IntegerOverflowError::new(&self.line))?;
-        state.push_to_stack(result);
+        let value = state.pop_from_stack(&self.line)?;
+        let is_zero = match &value {
+            Value::Int(value) => *value == 0,
+            Value::BigInt(value) => value.is_zero(),
+            Value::Decimal(value) => value.mantissa() == 0,
+            Value::Float(value) => *value == 0.0,
+            Value::Bool(value) => !value,
+            other => Err(TypeMismatchError::new("Int", other.type_name(), &self.line))?,
+        };
+        if is_zero {
+            let details = format!("Asserted value is {}", value);
+            return Err(AssertionFailedError::new(details, &self.line))?;
+        }
         state.inc_command_pointer();
         Ok(())
     }
 }
 
 #[derive(Debug)]
-pub struct MulCommand {
+pub struct AssertEqCommand {
     line: String,
 }
 
-impl MulCommand {
+impl AssertEqCommand {
     pub fn new(line: String) -> Self {
-        MulCommand { line }
+        AssertEqCommand { line }
     }
 }
 
-impl Command for MulCommand {
+impl Command for AssertEqCommand {
     fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
         let second = state.pop_from_stack(&self.line)?;
         let first = state.pop_from_stack(&self.line)?;
-        let result = first
-            .checked_mul(second)
-            .ok_or(IntegerOverflowError::new(&self.line))?;
-        state.push_to_stack(result);
+        if first != second {
+            let details = format!(
+                "{} {} is not equal to {} {}",
+                first.type_name(),
+                first,
+                second.type_name(),
+                second
+            );
+            return Err(AssertionFailedError::new(details, &self.line))?;
+        }
         state.inc_command_pointer();
         Ok(())
     }
 }
 
 #[derive(Debug)]
-pub struct DivCommand {
+pub struct CallNativeCommand {
+    name: String,
     line: String,
 }
 
-impl DivCommand {
+impl CallNativeCommand {
+    pub fn new(name: String, line: String) -> Self {
+        CallNativeCommand { name, line }
+    }
+}
+
+impl Command for CallNativeCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let arity = match state.get_natives().get(&self.name) {
+            Some(native) => native.get_arity(),
+            None => Err(LinkError::from(UnknownNativeFunctionError::new(&self.line)))?,
+        };
+        let mut arguments = Vec::with_capacity(arity);
+        for _ in 0..arity {
+            arguments.push(state.pop_from_stack(&self.line)?);
+        }
+        arguments.reverse();
+        let results = state
+            .get_natives()
+            .get(&self.name)
+            .unwrap()
+            .call(&arguments)
+            .map_err(|details| NativeCallError::new(details, &self.line))?;
+        for result in results {
+            state.push_to_stack(result);
+        }
+        state.inc_command_pointer();
+        Ok(())
+    }
+
+    fn link(&self, state: &State) -> Result<(), LinkError> {
+        if !state.get_natives().contains(&self.name) {
+            return Err(UnknownNativeFunctionError::new(&self.line))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct DumpCommand {
+    line: String,
+}
+
+impl DumpCommand {
     pub fn new(line: String) -> Self {
-        DivCommand { line }
+        DumpCommand { line }
     }
 }
 
-impl Command for DivCommand {
+impl Command for DumpCommand {
     fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
-        let second = state.pop_from_stack(&self.line)?;
-        let first = state.pop_from_stack(&self.line)?;
-        let result = first
-            .checked_div(second)
-            .ok_or(IntegerOverflowError::new(&self.line))?;
-        state.push_to_stack(result);
+        let text = format!("DUMP LINE => {}\n{}", self.line, state.describe());
+        state.write_debug(&text, &self.line)?;
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct BreakpointCommand {
+    line: String,
+}
+
+impl BreakpointCommand {
+    pub fn new(line: String) -> Self {
+        BreakpointCommand { line }
+    }
+}
+
+impl Command for BreakpointCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        state.trigger_breakpoint(&self.line);
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+fn sandbox_error(error: SandboxError, line: &str) -> RuntimeError {
+    match error {
+        SandboxError::PermissionDenied => PermissionDeniedError::new(line).into(),
+        SandboxError::Io(_) => FileIoError::new(line).into(),
+    }
+}
+
+#[derive(Debug)]
+pub struct FileReadIntCommand {
+    path: String,
+    line: String,
+}
+
+impl FileReadIntCommand {
+    pub fn new(path: String, line: String) -> Self {
+        FileReadIntCommand { path, line }
+    }
+}
+
+impl Command for FileReadIntCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let content = state
+            .get_file_sandbox(&self.line)?
+            .read_to_string(&self.path)
+            .map_err(|error| sandbox_error(error, &self.line))?;
+        let value: i32 = content
+            .trim()
+            .parse()
+            .map_err(|_| StringParseError::new(&self.line))?;
+        state.push_to_stack(Value::Int(value));
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct FileAppendCommand {
+    path: String,
+    line: String,
+}
+
+impl FileAppendCommand {
+    pub fn new(path: String, line: String) -> Self {
+        FileAppendCommand { path, line }
+    }
+}
+
+impl Command for FileAppendCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        // Checked before popping, so the value stays on the stack when denied
+        let sandbox = state.get_file_sandbox(&self.line)?.clone();
+        let value = state.pop_from_stack(&self.line)?;
+        sandbox
+            .append(&self.path, &format!("{}\n", value))
+            .map_err(|error| sandbox_error(error, &self.line))?;
         state.inc_command_pointer();
         Ok(())
     }
@@ -138,7 +1291,7 @@ impl WriteVariableCommand {
 impl Command for WriteVariableCommand {
     fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
         let variable = state.pop_from_stack(&self.line)?;
-        state.memory_insert(self.variable_name.clone(), variable);
+        state.memory_insert(self.variable_name.clone(), variable, &self.line)?;
         state.inc_command_pointer();
         Ok(())
     }
@@ -169,18 +1322,102 @@ impl Command for ReadVariableCommand {
 }
 
 #[derive(Debug)]
-pub struct ReturnCommand {
+pub struct ConstVariableCommand {
+    variable_name: String,
+    line: String,
+}
+
+impl ConstVariableCommand {
+    pub fn new(variable_name: String, line: String) -> Self {
+        ConstVariableCommand {
+            variable_name,
+            line,
+        }
+    }
+}
+
+impl Command for ConstVariableCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let variable = state.pop_from_stack(&self.line)?;
+        state.memory_insert_const(self.variable_name.clone(), variable, &self.line)?;
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct DeleteVariableCommand {
+    variable_name: String,
+    line: String,
+}
+
+impl DeleteVariableCommand {
+    pub fn new(variable_name: String, line: String) -> Self {
+        DeleteVariableCommand {
+            variable_name,
+            line,
+        }
+    }
+}
+
+impl Command for DeleteVariableCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        state.memory_remove(&self.variable_name, &self.line)?;
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct HasVariableCommand {
+    variable_name: String,
     _line: String,
 }
 
+impl HasVariableCommand {
+    pub fn new(variable_name: String, _line: String) -> Self {
+        HasVariableCommand {
+            variable_name,
+            _line,
+        }
+    }
+}
+
+impl Command for HasVariableCommand {
+    fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        let exists = state.memory_contains(&self.variable_name);
+        state.push_to_stack(Value::Int(exists as i32));
+        state.inc_command_pointer();
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct ReturnCommand {
+    count: Option<usize>,
+    line: String,
+}
+
 impl ReturnCommand {
-    pub fn new(_line: String) -> Self {
-        ReturnCommand { _line }
+    pub fn new(line: String) -> Self {
+        ReturnCommand { count: None, line }
+    }
+
+    /// `RETURN n`, which returns the top `count` values of the stack.
+    pub fn with_count(count: usize, line: String) -> Self {
+        ReturnCommand {
+            count: Some(count),
+            line,
+        }
     }
 }
 
 impl Command for ReturnCommand {
     fn execute(&self, state: &mut State) -> Result<(), RuntimeError> {
+        if let Some(count) = self.count {
+            let values = state.pop_many_from_stack(count, &self.line)?;
+            state.set_return_values(values);
+        }
         state.set_ret_status(true);
         state.inc_command_pointer();
         Ok(())
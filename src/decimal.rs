@@ -0,0 +1,294 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+/// Rounding applied when a decimal result has more fractional digits than
+/// the target scale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// Round half away from zero.
+    HalfUp,
+    /// Round half to the nearest even digit (banker's rounding).
+    HalfEven,
+    /// Truncate toward zero.
+    Down,
+    /// Round away from zero.
+    Up,
+    /// Round toward negative infinity.
+    Floor,
+    /// Round toward positive infinity.
+    Ceiling,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseRoundingModeError;
+
+impl FromStr for RoundingMode {
+    type Err = ParseRoundingModeError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "HALF_UP" => Ok(RoundingMode::HalfUp),
+            "HALF_EVEN" => Ok(RoundingMode::HalfEven),
+            "DOWN" => Ok(RoundingMode::Down),
+            "UP" => Ok(RoundingMode::Up),
+            "FLOOR" => Ok(RoundingMode::Floor),
+            "CEILING" => Ok(RoundingMode::Ceiling),
+            _ => Err(ParseRoundingModeError),
+        }
+    }
+}
+
+/// Fixed-point decimal number `mantissa * 10^-scale`. Numbers are compared
+/// by value, so `1.50` equals `1.5`.
+#[derive(Debug, Clone, Copy)]
+pub struct Decimal {
+    mantissa: i64,
+    scale: u32,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseDecimalError;
+
+fn power_of_ten(exponent: u32) -> Option<i128> {
+    10i128.checked_pow(exponent)
+}
+
+/// Divides `numerator` by `denominator` rounding the quotient with `mode`.
+fn divide_rounded(numerator: i128, denominator: i128, mode: RoundingMode) -> Option<i128> {
+    let quotient = numerator.checked_div(denominator)?;
+    let remainder = numerator % denominator;
+    if remainder == 0 {
+        return Some(quotient);
+    }
+    let negative = (numerator < 0) != (denominator < 0);
+    let away_from_zero = if negative { -1 } else { 1 };
+    let doubled_remainder = remainder.unsigned_abs() * 2;
+    let half = doubled_remainder.cmp(&denominator.unsigned_abs());
+    let round_away = match mode {
+        RoundingMode::Down => false,
+        RoundingMode::Up => true,
+        RoundingMode::Floor => negative,
+        RoundingMode::Ceiling => !negative,
+        RoundingMode::HalfUp => half.is_ge(),
+        RoundingMode::HalfEven => half.is_gt() || (half.is_eq() && quotient % 2 != 0),
+    };
+    if round_away {
+        quotient.checked_add(away_from_zero)
+    } else {
+        Some(quotient)
+    }
+}
+
+impl Decimal {
+    pub fn new(mantissa: i64, scale: u32) -> Self {
+        Decimal { mantissa, scale }
+    }
+
+    pub fn mantissa(&self) -> i64 {
+        self.mantissa
+    }
+
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    /// Mantissa and scale without trailing zeros in the fraction.
+    fn normalized(&self) -> (i64, u32) {
+        let (mut mantissa, mut scale) = (self.mantissa, self.scale);
+        while scale > 0 && mantissa % 10 == 0 {
+            mantissa /= 10;
+            scale -= 1;
+        }
+        (mantissa, scale)
+    }
+
+    fn from_scaled(mantissa: i128, scale: u32) -> Option<Self> {
+        Some(Decimal::new(i64::try_from(mantissa).ok()?, scale))
+    }
+
+    /// Mantissa expressed with `scale` fractional digits, `scale` must not be
+    /// smaller than the current one.
+    fn widened_mantissa(&self, scale: u32) -> Option<i128> {
+        (self.mantissa as i128).checked_mul(power_of_ten(scale - self.scale)?)
+    }
+
+    pub fn rescale(&self, scale: u32, mode: RoundingMode) -> Option<Decimal> {
+        if scale >= self.scale {
+            Decimal::from_scaled(self.widened_mantissa(scale)?, scale)
+        } else {
+            let divisor = power_of_ten(self.scale - scale)?;
+            let mantissa = divide_rounded(self.mantissa as i128, divisor, mode)?;
+            Decimal::from_scaled(mantissa, scale)
+        }
+    }
+
+    pub fn checked_add(&self, other: &Decimal) -> Option<Decimal> {
+        let scale = self.scale.max(other.scale);
+        let mantissa = self
+            .widened_mantissa(scale)?
+            .checked_add(other.widened_mantissa(scale)?)?;
+        Decimal::from_scaled(mantissa, scale)
+    }
+
+    pub fn checked_sub(&self, other: &Decimal) -> Option<Decimal> {
+        let scale = self.scale.max(other.scale);
+        let mantissa = self
+            .widened_mantissa(scale)?
+            .checked_sub(other.widened_mantissa(scale)?)?;
+        Decimal::from_scaled(mantissa, scale)
+    }
+
+    pub fn checked_mul(&self, other: &Decimal, scale: u32, mode: RoundingMode) -> Option<Decimal> {
+        let mantissa = (self.mantissa as i128).checked_mul(other.mantissa as i128)?;
+        let product_scale = self.scale.checked_add(other.scale)?;
+        let mantissa = if scale >= product_scale {
+            mantissa.checked_mul(power_of_ten(scale - product_scale)?)?
+        } else {
+            divide_rounded(mantissa, power_of_ten(product_scale - scale)?, mode)?
+        };
+        Decimal::from_scaled(mantissa, scale)
+    }
+
+    /// Returns `None` on overflow or when dividing by zero.
+    pub fn checked_div(&self, other: &Decimal, scale: u32, mode: RoundingMode) -> Option<Decimal> {
+        if other.mantissa == 0 {
+            return None;
+        }
+        let mut numerator = self.mantissa as i128;
+        let mut denominator = other.mantissa as i128;
+        let exponent = scale.checked_add(other.scale)? as i64 - self.scale as i64;
+        if exponent >= 0 {
+            numerator = numerator.checked_mul(power_of_ten(exponent as u32)?)?;
+        } else {
+            denominator = denominator.checked_mul(power_of_ten((-exponent) as u32)?)?;
+        }
+        Decimal::from_scaled(divide_rounded(numerator, denominator, mode)?, scale)
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.normalized() == other.normalized()
+    }
+}
+
+impl Eq for Decimal {}
+
+impl Hash for Decimal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.normalized().hash(state);
+    }
+}
+
+impl From<i32> for Decimal {
+    fn from(value: i32) -> Self {
+        Decimal::new(value as i64, 0)
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = ParseDecimalError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match input.as_bytes().first() {
+            Some(b'-') => (true, &input[1..]),
+            Some(b'+') => (false, &input[1..]),
+            _ => (false, input),
+        };
+        let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        let all_digits = |part: &str| part.bytes().all(|digit| digit.is_ascii_digit());
+        if integer.is_empty() || !all_digits(integer) || !all_digits(fraction) {
+            return Err(ParseDecimalError);
+        }
+        let mut mantissa: i64 = 0;
+        for digit in integer.bytes().chain(fraction.bytes()) {
+            mantissa = mantissa
+                .checked_mul(10)
+                .and_then(|mantissa| mantissa.checked_add((digit - b'0') as i64))
+                .ok_or(ParseDecimalError)?;
+        }
+        if negative {
+            mantissa = -mantissa;
+        }
+        Ok(Decimal::new(mantissa, fraction.len() as u32))
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let scale = self.scale as usize;
+        if scale == 0 {
+            return write!(formatter, "{}{}", sign, digits);
+        }
+        let digits = format!("{:0>width$}", digits, width = scale + 1);
+        let (integer, fraction) = digits.split_at(digits.len() - scale);
+        write!(formatter, "{}{}.{}", sign, integer, fraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(input: &str) -> Decimal {
+        input.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        assert_eq!(dec("12.34"), Decimal::new(1234, 2));
+        assert_eq!(dec("-0.05").to_string(), "-0.05");
+        assert_eq!(dec("7").to_string(), "7");
+        assert!("1.2.3".parse::<Decimal>().is_err());
+        assert!(".5".parse::<Decimal>().is_err());
+        assert!("99999999999999999999".parse::<Decimal>().is_err());
+    }
+
+    #[test]
+    fn test_rounding_modes() {
+        let value = dec("2.345");
+        let rescale = |mode| value.rescale(2, mode).unwrap().to_string();
+        assert_eq!(rescale(RoundingMode::HalfUp), "2.35");
+        assert_eq!(rescale(RoundingMode::HalfEven), "2.34");
+        assert_eq!(rescale(RoundingMode::Down), "2.34");
+        assert_eq!(rescale(RoundingMode::Up), "2.35");
+        let negative = dec("-2.341");
+        let rescale = |mode| negative.rescale(2, mode).unwrap().to_string();
+        assert_eq!(rescale(RoundingMode::Floor), "-2.35");
+        assert_eq!(rescale(RoundingMode::Ceiling), "-2.34");
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let mode = RoundingMode::HalfEven;
+        assert_eq!(dec("1.5").checked_add(&dec("0.25")).unwrap(), dec("1.75"));
+        assert_eq!(dec("1.5").checked_sub(&dec("2")).unwrap(), dec("-0.5"));
+        assert_eq!(
+            dec("19.99").checked_mul(&dec("0.075"), 2, mode).unwrap(),
+            dec("1.50")
+        );
+        assert_eq!(
+            dec("10")
+                .checked_div(&dec("3"), 2, RoundingMode::Up)
+                .unwrap(),
+            dec("3.34")
+        );
+        assert!(dec("1").checked_div(&dec("0"), 2, mode).is_none());
+        assert!(Decimal::new(i64::MAX, 0).checked_add(&dec("1")).is_none());
+        let tiny = Decimal::new(1, u32::MAX);
+        assert!(tiny.checked_mul(&tiny, 2, mode).is_none());
+        assert!(dec("1").checked_div(&tiny, u32::MAX, mode).is_none());
+    }
+
+    #[test]
+    fn test_equality_ignores_scale() {
+        assert_eq!(dec("1.50"), dec("1.5"));
+        assert_eq!(dec("0.00"), dec("0"));
+        assert_ne!(dec("1.05"), dec("1.5"));
+        let keys: std::collections::HashSet<Decimal> = [dec("2.0"), dec("2")].into();
+        assert_eq!(keys.len(), 1);
+    }
+}
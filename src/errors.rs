@@ -1,13 +1,72 @@
+use crate::span::Span;
 use std::convert::From;
 use std::error::Error;
 use std::fmt;
 
+/// Message followed by the line of code which caused it, if known.
+fn describe(message: &str, line: Option<&str>) -> String {
+    match line {
+        Some(line) => format!("{} LINE => {}", message, line),
+        None => message.to_owned(),
+    }
+}
+
+/// Renders `message` in the rustc style when its location is known,
+/// otherwise together with the line of code.
+fn render_diagnostic(message: &str, line: Option<&str>, span: Option<&Span>) -> String {
+    match span {
+        Some(span) => span.render(message),
+        None => format!("error: {}", describe(message, line)),
+    }
+}
+
 #[derive(Debug)]
-pub struct RuntimeError(String);
+pub struct RuntimeError {
+    message: String,
+    line: Option<String>,
+    span: Option<Span>,
+}
+
+impl RuntimeError {
+    fn new(message: String) -> Self {
+        RuntimeError {
+            message,
+            line: None,
+            span: None,
+        }
+    }
+
+    fn at_line(message: String, line: &str) -> Self {
+        RuntimeError {
+            line: Some(line.to_owned()),
+            ..RuntimeError::new(message)
+        }
+    }
+
+    /// Description of the error without the line of code.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn span(&self) -> Option<&Span> {
+        self.span.as_ref()
+    }
+
+    /// Points the error at `span` unless it already has a location.
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span.get_or_insert(span);
+        self
+    }
+
+    pub fn render(&self) -> String {
+        render_diagnostic(&self.message, self.line.as_deref(), self.span.as_ref())
+    }
+}
 
 impl fmt::Display for RuntimeError {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        write!(formatter, "RuntimeError -> {}", &self.0)
+        let message = describe(&self.message, self.line.as_deref());
+        write!(formatter, "RuntimeError -> {}", message)
     }
 }
 
@@ -27,15 +86,14 @@ impl<'a> fmt::Display for EmptyStackError<'a> {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         write!(
             formatter,
-            "EmpyStackError: There is no obligatory parameter in the stack. LINE => {}",
-            self.message
+            "EmpyStackError: There is no obligatory parameter in the stack."
         )
     }
 }
 
 impl<'a> From<EmptyStackError<'a>> for RuntimeError {
     fn from(error: EmptyStackError) -> Self {
-        RuntimeError(error.to_string())
+        RuntimeError::at_line(error.to_string(), error.message)
     }
 }
 
@@ -51,15 +109,126 @@ impl<'a> IntegerOverflowError<'a> {
 
 impl<'a> fmt::Display for IntegerOverflowError<'a> {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        write!(formatter, "IntegerOverflowError: Result of an integer operation does not fit within the allocated memory space. LINE => {}", self.message)
+        write!(formatter, "IntegerOverflowError: Result of an integer operation does not fit within the allocated memory space.")
     }
 }
 
 impl<'a> From<IntegerOverflowError<'a>> for RuntimeError {
     fn from(error: IntegerOverflowError) -> Self {
-        RuntimeError(error.to_string())
+        RuntimeError::at_line(error.to_string(), error.message)
+    }
+}
+pub struct TypeMismatchError<'a> {
+    expected: &'static str,
+    found: &'static str,
+    message: &'a str,
+}
+
+impl<'a> TypeMismatchError<'a> {
+    pub fn new(expected: &'static str, found: &'static str, message: &'a str) -> Self {
+        TypeMismatchError {
+            expected,
+            found,
+            message,
+        }
+    }
+}
+
+impl<'a> fmt::Display for TypeMismatchError<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "TypeMismatchError: Expected operand of type {} but found {}.",
+            self.expected, self.found
+        )
+    }
+}
+
+impl<'a> From<TypeMismatchError<'a>> for RuntimeError {
+    fn from(error: TypeMismatchError) -> Self {
+        RuntimeError::at_line(error.to_string(), error.message)
+    }
+}
+
+pub struct DecimalOverflowError<'a> {
+    message: &'a str,
+}
+
+impl<'a> DecimalOverflowError<'a> {
+    pub fn new(message: &'a str) -> Self {
+        DecimalOverflowError { message }
+    }
+}
+
+impl<'a> fmt::Display for DecimalOverflowError<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "DecimalOverflowError: Result of a decimal operation does not fit within the allocated memory space or division by zero.")
+    }
+}
+
+impl<'a> From<DecimalOverflowError<'a>> for RuntimeError {
+    fn from(error: DecimalOverflowError) -> Self {
+        RuntimeError::at_line(error.to_string(), error.message)
+    }
+}
+
+pub struct StringParseError<'a> {
+    message: &'a str,
+}
+
+impl<'a> StringParseError<'a> {
+    pub fn new(message: &'a str) -> Self {
+        StringParseError { message }
+    }
+}
+
+impl<'a> fmt::Display for StringParseError<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "StringParseError: String can not be converted to a number."
+        )
+    }
+}
+
+impl<'a> From<StringParseError<'a>> for RuntimeError {
+    fn from(error: StringParseError) -> Self {
+        RuntimeError::at_line(error.to_string(), error.message)
+    }
+}
+
+pub struct IndexOutOfBoundsError<'a> {
+    index: i32,
+    length: usize,
+    message: &'a str,
+}
+
+impl<'a> IndexOutOfBoundsError<'a> {
+    pub fn new(index: i32, length: usize, message: &'a str) -> Self {
+        IndexOutOfBoundsError {
+            index,
+            length,
+            message,
+        }
     }
 }
+
+impl<'a> fmt::Display for IndexOutOfBoundsError<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "IndexOutOfBoundsError: Index {} is out of range for length {}.",
+            self.index, self.length
+        )
+    }
+}
+
+impl<'a> From<IndexOutOfBoundsError<'a>> for RuntimeError {
+    fn from(error: IndexOutOfBoundsError) -> Self {
+        RuntimeError::at_line(error.to_string(), error.message)
+    }
+}
+
 pub struct UnknownVariableLoadingError<'a> {
     message: &'a str,
 }
@@ -74,15 +243,285 @@ impl<'a> fmt::Display for UnknownVariableLoadingError<'a> {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         write!(
             formatter,
-            "UnkonwnVariableLoadingError: There is no such variable in the context. LINE => {}",
-            self.message
+            "UnkonwnVariableLoadingError: There is no such variable in the context."
         )
     }
 }
 
 impl<'a> From<UnknownVariableLoadingError<'a>> for RuntimeError {
     fn from(error: UnknownVariableLoadingError) -> Self {
-        RuntimeError(error.to_string())
+        RuntimeError::at_line(error.to_string(), error.message)
+    }
+}
+
+pub struct UnknownKeyError<'a> {
+    message: &'a str,
+}
+
+impl<'a> UnknownKeyError<'a> {
+    pub fn new(message: &'a str) -> Self {
+        UnknownKeyError { message }
+    }
+}
+
+impl<'a> fmt::Display for UnknownKeyError<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "UnknownKeyError: There is no such key in the map."
+        )
+    }
+}
+
+impl<'a> From<UnknownKeyError<'a>> for RuntimeError {
+    fn from(error: UnknownKeyError) -> Self {
+        RuntimeError::at_line(error.to_string(), error.message)
+    }
+}
+
+pub struct OutputError<'a> {
+    message: &'a str,
+}
+
+impl<'a> OutputError<'a> {
+    pub fn new(message: &'a str) -> Self {
+        OutputError { message }
+    }
+}
+
+impl<'a> fmt::Display for OutputError<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "OutputError: Value can not be written to the output."
+        )
+    }
+}
+
+impl<'a> From<OutputError<'a>> for RuntimeError {
+    fn from(error: OutputError) -> Self {
+        RuntimeError::at_line(error.to_string(), error.message)
+    }
+}
+
+pub struct InvalidCharacterError<'a> {
+    code: i32,
+    message: &'a str,
+}
+
+impl<'a> InvalidCharacterError<'a> {
+    pub fn new(code: i32, message: &'a str) -> Self {
+        InvalidCharacterError { code, message }
+    }
+}
+
+impl<'a> fmt::Display for InvalidCharacterError<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "InvalidCharacterError: {} is not a valid character code.",
+            self.code
+        )
+    }
+}
+
+impl<'a> From<InvalidCharacterError<'a>> for RuntimeError {
+    fn from(error: InvalidCharacterError) -> Self {
+        RuntimeError::at_line(error.to_string(), error.message)
+    }
+}
+
+pub struct InputExhaustedError<'a> {
+    message: &'a str,
+}
+
+impl<'a> InputExhaustedError<'a> {
+    pub fn new(message: &'a str) -> Self {
+        InputExhaustedError { message }
+    }
+}
+
+impl<'a> fmt::Display for InputExhaustedError<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "InputExhaustedError: There is no more input to read."
+        )
+    }
+}
+
+impl<'a> From<InputExhaustedError<'a>> for RuntimeError {
+    fn from(error: InputExhaustedError) -> Self {
+        RuntimeError::at_line(error.to_string(), error.message)
+    }
+}
+
+pub struct InputError<'a> {
+    message: &'a str,
+}
+
+impl<'a> InputError<'a> {
+    pub fn new(message: &'a str) -> Self {
+        InputError { message }
+    }
+}
+
+impl<'a> fmt::Display for InputError<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "InputError: Input can not be read or is not an integer."
+        )
+    }
+}
+
+impl<'a> From<InputError<'a>> for RuntimeError {
+    fn from(error: InputError) -> Self {
+        RuntimeError::at_line(error.to_string(), error.message)
+    }
+}
+
+pub struct AssertionFailedError<'a> {
+    details: String,
+    message: &'a str,
+}
+
+impl<'a> AssertionFailedError<'a> {
+    pub fn new(details: String, message: &'a str) -> Self {
+        AssertionFailedError { details, message }
+    }
+}
+
+impl<'a> fmt::Display for AssertionFailedError<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "AssertionFailedError: {}.", self.details)
+    }
+}
+
+impl<'a> From<AssertionFailedError<'a>> for RuntimeError {
+    fn from(error: AssertionFailedError) -> Self {
+        RuntimeError::at_line(error.to_string(), error.message)
+    }
+}
+
+pub struct NativeCallError<'a> {
+    details: String,
+    message: &'a str,
+}
+
+impl<'a> NativeCallError<'a> {
+    pub fn new(details: String, message: &'a str) -> Self {
+        NativeCallError { details, message }
+    }
+}
+
+impl<'a> fmt::Display for NativeCallError<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "NativeCallError: Native function failed: {}.",
+            self.details
+        )
+    }
+}
+
+impl<'a> From<NativeCallError<'a>> for RuntimeError {
+    fn from(error: NativeCallError) -> Self {
+        RuntimeError::at_line(error.to_string(), error.message)
+    }
+}
+
+pub struct ConstReassignmentError<'a> {
+    message: &'a str,
+}
+
+impl<'a> ConstReassignmentError<'a> {
+    pub fn new(message: &'a str) -> Self {
+        ConstReassignmentError { message }
+    }
+}
+
+impl<'a> fmt::Display for ConstReassignmentError<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "ConstReassignmentError: The variable is constant and can not be changed."
+        )
+    }
+}
+
+impl<'a> From<ConstReassignmentError<'a>> for RuntimeError {
+    fn from(error: ConstReassignmentError) -> Self {
+        RuntimeError::at_line(error.to_string(), error.message)
+    }
+}
+
+pub struct PermissionDeniedError<'a> {
+    message: &'a str,
+}
+
+impl<'a> PermissionDeniedError<'a> {
+    pub fn new(message: &'a str) -> Self {
+        PermissionDeniedError { message }
+    }
+}
+
+impl<'a> fmt::Display for PermissionDeniedError<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "PermissionDeniedError: File access is not granted or the path is outside of the granted directory.")
+    }
+}
+
+impl<'a> From<PermissionDeniedError<'a>> for RuntimeError {
+    fn from(error: PermissionDeniedError) -> Self {
+        RuntimeError::at_line(error.to_string(), error.message)
+    }
+}
+
+pub struct FileIoError<'a> {
+    message: &'a str,
+}
+
+impl<'a> FileIoError<'a> {
+    pub fn new(message: &'a str) -> Self {
+        FileIoError { message }
+    }
+}
+
+impl<'a> fmt::Display for FileIoError<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "FileIoError: File can not be read or written.")
+    }
+}
+
+impl<'a> From<FileIoError<'a>> for RuntimeError {
+    fn from(error: FileIoError) -> Self {
+        RuntimeError::at_line(error.to_string(), error.message)
+    }
+}
+
+pub struct HeapLimitError<'a> {
+    message: &'a str,
+}
+
+impl<'a> HeapLimitError<'a> {
+    pub fn new(message: &'a str) -> Self {
+        HeapLimitError { message }
+    }
+}
+
+impl<'a> fmt::Display for HeapLimitError<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "HeapLimitError: Arrays and maps exceed the limit of elements on the heap."
+        )
+    }
+}
+
+impl<'a> From<HeapLimitError<'a>> for RuntimeError {
+    fn from(error: HeapLimitError) -> Self {
+        RuntimeError::at_line(error.to_string(), error.message)
     }
 }
 
@@ -96,7 +535,7 @@ impl fmt::Display for NoReturnCommandError {
 
 impl From<NoReturnCommandError> for RuntimeError {
     fn from(error: NoReturnCommandError) -> Self {
-        RuntimeError(error.to_string())
+        RuntimeError::new(error.to_string())
     }
 }
 
@@ -114,15 +553,14 @@ impl<'a> fmt::Display for LoopInitError<'a> {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         write!(
             formatter,
-            "LoopInitError: Loop was not initialized approprietly LINE => {}",
-            self.message
+            "LoopInitError: Loop was not initialized approprietly"
         )
     }
 }
 
 impl<'a> From<LoopInitError<'a>> for RuntimeError {
     fn from(error: LoopInitError) -> Self {
-        RuntimeError(error.to_string())
+        RuntimeError::at_line(error.to_string(), error.message)
     }
 }
 
@@ -136,7 +574,7 @@ impl fmt::Display for NoLoopInstanceError {
 
 impl From<NoLoopInstanceError> for RuntimeError {
     fn from(error: NoLoopInstanceError) -> Self {
-        RuntimeError(error.to_string())
+        RuntimeError::new(error.to_string())
     }
 }
 
@@ -152,17 +590,13 @@ impl<'a> NestedLoopsError<'a> {
 
 impl<'a> fmt::Display for NestedLoopsError<'a> {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            formatter,
-            "NestedLoopsError: Nested loops are forbiden LINE => {}",
-            self.message
-        )
+        write!(formatter, "NestedLoopsError: Nested loops are forbiden")
     }
 }
 
 impl<'a> From<NestedLoopsError<'a>> for RuntimeError {
     fn from(error: NestedLoopsError) -> Self {
-        RuntimeError(error.to_string())
+        RuntimeError::at_line(error.to_string(), error.message)
     }
 }
 
@@ -176,22 +610,63 @@ impl fmt::Display for NoCodeError {
 
 impl From<NoCodeError> for RuntimeError {
     fn from(error: NoCodeError) -> Self {
-        RuntimeError(error.to_string())
+        RuntimeError::new(error.to_string())
     }
 }
 
 impl From<NoCodeError> for ParserError {
     fn from(error: NoCodeError) -> Self {
-        ParserError(error.to_string())
+        ParserError::new(error.to_string())
     }
 }
 
 #[derive(Debug)]
-pub struct ParserError(String);
+pub struct ParserError {
+    message: String,
+    line: Option<String>,
+    span: Option<Span>,
+}
+
+impl ParserError {
+    fn new(message: String) -> Self {
+        ParserError {
+            message,
+            line: None,
+            span: None,
+        }
+    }
+
+    fn at_line(message: String, line: &str) -> Self {
+        ParserError {
+            line: Some(line.to_owned()),
+            ..ParserError::new(message)
+        }
+    }
+
+    /// Description of the error without the line of code.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn span(&self) -> Option<&Span> {
+        self.span.as_ref()
+    }
+
+    /// Points the error at `span` unless it already has a location.
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span.get_or_insert(span);
+        self
+    }
+
+    pub fn render(&self) -> String {
+        render_diagnostic(&self.message, self.line.as_deref(), self.span.as_ref())
+    }
+}
 
 impl fmt::Display for ParserError {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        write!(formatter, "ParseError -> {}", &self.0)
+        let message = describe(&self.message, self.line.as_deref());
+        write!(formatter, "ParseError -> {}", message)
     }
 }
 
@@ -211,14 +686,327 @@ impl<'a> fmt::Display for UnknownCommandError<'a> {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         write!(
             formatter,
-            "UknownCommandError: The command is unkonwn or syntax is wrong LINE => {}",
-            self.message
+            "UknownCommandError: The command is unkonwn or syntax is wrong"
         )
     }
 }
 
 impl<'a> From<UnknownCommandError<'a>> for ParserError {
     fn from(error: UnknownCommandError) -> Self {
-        ParserError(error.to_string())
+        ParserError::at_line(error.to_string(), error.message)
+    }
+}
+
+#[derive(Debug)]
+pub struct LinkError {
+    message: String,
+    line: String,
+    span: Option<Span>,
+}
+
+impl LinkError {
+    fn at_line(message: String, line: &str) -> Self {
+        LinkError {
+            message,
+            line: line.to_owned(),
+            span: None,
+        }
+    }
+
+    pub fn span(&self) -> Option<&Span> {
+        self.span.as_ref()
+    }
+
+    /// Points the error at `span` unless it already has a location.
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span.get_or_insert(span);
+        self
+    }
+}
+
+impl fmt::Display for LinkError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let message = describe(&self.message, Some(&self.line));
+        write!(formatter, "LinkError -> {}", message)
+    }
+}
+
+impl Error for LinkError {}
+
+impl From<LinkError> for RuntimeError {
+    fn from(error: LinkError) -> Self {
+        RuntimeError {
+            message: format!("LinkError -> {}", error.message),
+            line: Some(error.line),
+            span: error.span,
+        }
+    }
+}
+
+pub struct ArgumentCountError<'a> {
+    command: &'a str,
+    expected: String,
+    found: usize,
+    message: &'a str,
+}
+
+impl<'a> ArgumentCountError<'a> {
+    pub fn new(command: &'a str, expected: String, found: usize, message: &'a str) -> Self {
+        ArgumentCountError {
+            command,
+            expected,
+            found,
+            message,
+        }
+    }
+}
+
+impl<'a> fmt::Display for ArgumentCountError<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "ArgumentCountError: {} takes {} argument(s) but {} were given.",
+            self.command, self.expected, self.found
+        )
+    }
+}
+
+impl<'a> From<ArgumentCountError<'a>> for ParserError {
+    fn from(error: ArgumentCountError) -> Self {
+        ParserError::at_line(error.to_string(), error.message)
+    }
+}
+
+pub struct ArgumentTypeError<'a> {
+    command: &'a str,
+    expected: &'static str,
+    found: &'a str,
+    message: &'a str,
+}
+
+impl<'a> ArgumentTypeError<'a> {
+    pub fn new(command: &'a str, expected: &'static str, found: &'a str, message: &'a str) -> Self {
+        ArgumentTypeError {
+            command,
+            expected,
+            found,
+            message,
+        }
+    }
+}
+
+impl<'a> fmt::Display for ArgumentTypeError<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "ArgumentTypeError: Argument of {} must be {} but found {}.",
+            self.command, self.expected, self.found
+        )
+    }
+}
+
+impl<'a> From<ArgumentTypeError<'a>> for ParserError {
+    fn from(error: ArgumentTypeError) -> Self {
+        ParserError::at_line(error.to_string(), error.message)
+    }
+}
+
+pub struct UnterminatedQuoteError<'a> {
+    message: &'a str,
+}
+
+impl<'a> UnterminatedQuoteError<'a> {
+    pub fn new(message: &'a str) -> Self {
+        UnterminatedQuoteError { message }
+    }
+}
+
+impl<'a> fmt::Display for UnterminatedQuoteError<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "UnterminatedQuoteError: Quoted argument is not closed."
+        )
+    }
+}
+
+impl<'a> From<UnterminatedQuoteError<'a>> for ParserError {
+    fn from(error: UnterminatedQuoteError) -> Self {
+        ParserError::at_line(error.to_string(), error.message)
+    }
+}
+
+pub struct InvalidEscapeError<'a> {
+    message: &'a str,
+}
+
+impl<'a> InvalidEscapeError<'a> {
+    pub fn new(message: &'a str) -> Self {
+        InvalidEscapeError { message }
+    }
+}
+
+impl<'a> fmt::Display for InvalidEscapeError<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "InvalidEscapeError: Unknown escape sequence in quoted argument, use \\\" \\' \\\\ \\n or \\t.")
+    }
+}
+
+impl<'a> From<InvalidEscapeError<'a>> for ParserError {
+    fn from(error: InvalidEscapeError) -> Self {
+        ParserError::at_line(error.to_string(), error.message)
+    }
+}
+
+pub struct UnexpectedCharacterError<'a> {
+    message: &'a str,
+}
+
+impl<'a> UnexpectedCharacterError<'a> {
+    pub fn new(message: &'a str) -> Self {
+        UnexpectedCharacterError { message }
+    }
+}
+
+impl<'a> fmt::Display for UnexpectedCharacterError<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "UnexpectedCharacterError: Closing quote must be followed by a whitespace."
+        )
+    }
+}
+
+impl<'a> From<UnexpectedCharacterError<'a>> for ParserError {
+    fn from(error: UnexpectedCharacterError) -> Self {
+        ParserError::at_line(error.to_string(), error.message)
+    }
+}
+
+pub struct InvalidIdentifierError<'a> {
+    name: &'a str,
+    message: &'a str,
+}
+
+impl<'a> InvalidIdentifierError<'a> {
+    pub fn new(name: &'a str, message: &'a str) -> Self {
+        InvalidIdentifierError { name, message }
+    }
+}
+
+impl<'a> fmt::Display for InvalidIdentifierError<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "InvalidIdentifierError: {:?} is not a valid variable name, use letters, digits and _ not starting with a digit.", self.name)
+    }
+}
+
+impl<'a> From<InvalidIdentifierError<'a>> for ParserError {
+    fn from(error: InvalidIdentifierError) -> Self {
+        ParserError::at_line(error.to_string(), error.message)
+    }
+}
+
+pub struct LiteralOutOfRangeError<'a> {
+    message: &'a str,
+}
+
+impl<'a> LiteralOutOfRangeError<'a> {
+    pub fn new(message: &'a str) -> Self {
+        LiteralOutOfRangeError { message }
+    }
+}
+
+impl<'a> fmt::Display for LiteralOutOfRangeError<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "LiteralOutOfRangeError: Integer literal is out of range of i32."
+        )
+    }
+}
+
+impl<'a> From<LiteralOutOfRangeError<'a>> for ParserError {
+    fn from(error: LiteralOutOfRangeError) -> Self {
+        ParserError::at_line(error.to_string(), error.message)
+    }
+}
+
+pub struct ReversedRangeError<'a> {
+    low: i32,
+    high: i32,
+    message: &'a str,
+}
+
+impl<'a> ReversedRangeError<'a> {
+    pub fn new(low: i32, high: i32, message: &'a str) -> Self {
+        ReversedRangeError { low, high, message }
+    }
+}
+
+impl<'a> fmt::Display for ReversedRangeError<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "ReversedRangeError: Bounds are reversed, low {} is above high {}.",
+            self.low, self.high
+        )
+    }
+}
+
+impl<'a> From<ReversedRangeError<'a>> for ParserError {
+    fn from(error: ReversedRangeError) -> Self {
+        ParserError::at_line(error.to_string(), error.message)
+    }
+}
+
+pub struct ConstRedefinitionError<'a> {
+    name: &'a str,
+    message: &'a str,
+}
+
+impl<'a> ConstRedefinitionError<'a> {
+    pub fn new(name: &'a str, message: &'a str) -> Self {
+        ConstRedefinitionError { name, message }
+    }
+}
+
+impl<'a> fmt::Display for ConstRedefinitionError<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "ConstRedefinitionError: Constant {} is already defined.",
+            self.name
+        )
+    }
+}
+
+impl<'a> From<ConstRedefinitionError<'a>> for ParserError {
+    fn from(error: ConstRedefinitionError) -> Self {
+        ParserError::at_line(error.to_string(), error.message)
+    }
+}
+
+pub struct UnknownNativeFunctionError<'a> {
+    message: &'a str,
+}
+
+impl<'a> UnknownNativeFunctionError<'a> {
+    pub fn new(message: &'a str) -> Self {
+        UnknownNativeFunctionError { message }
+    }
+}
+
+impl<'a> fmt::Display for UnknownNativeFunctionError<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "UnknownNativeFunctionError: There is no native function registered with such name."
+        )
+    }
+}
+
+impl<'a> From<UnknownNativeFunctionError<'a>> for LinkError {
+    fn from(error: UnknownNativeFunctionError) -> Self {
+        LinkError::at_line(error.to_string(), error.message)
     }
 }
@@ -0,0 +1,102 @@
+use crate::bigint::BigInt;
+use crate::decimal::Decimal;
+use crate::value::Value;
+use std::collections::HashMap;
+
+/// Handle of an object allocated on the `Heap`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HeapRef(usize);
+
+#[derive(Debug)]
+pub enum HeapObject {
+    Array(Vec<Value>),
+    Map(HashMap<MapKey, Value>),
+}
+
+impl HeapObject {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            HeapObject::Array(_) => "Array",
+            HeapObject::Map(_) => "Map",
+        }
+    }
+}
+
+/// Values which can be used as keys of a map. Floats and heap handles are
+/// excluded since they have no meaningful equality for lookups.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MapKey {
+    Int(i32),
+    BigInt(BigInt),
+    Decimal(Decimal),
+    Bool(bool),
+    Str(String),
+}
+
+impl TryFrom<Value> for MapKey {
+    type Error = Value;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::Int(value) => Ok(MapKey::Int(value)),
+            Value::BigInt(value) => Ok(MapKey::BigInt(value)),
+            Value::Decimal(value) => Ok(MapKey::Decimal(value)),
+            Value::Bool(value) => Ok(MapKey::Bool(value)),
+            Value::Str(value) => Ok(MapKey::Str(value)),
+            other => Err(other),
+        }
+    }
+}
+
+/// Number of elements all heap objects together may hold, an array element,
+/// a map entry and a map itself count as one each. Objects are never freed,
+/// so objects allocated in a loop count towards it as well.
+pub const MAX_HEAP_ELEMENTS: usize = 1 << 20;
+
+/// Storage for values which are referenced by handle instead of being copied,
+/// so the same object can be shared between the stack and variables.
+#[derive(Debug, Default)]
+pub struct Heap {
+    objects: Vec<HeapObject>,
+    elements: usize,
+}
+
+impl Heap {
+    pub fn new() -> Self {
+        Heap {
+            objects: Vec::new(),
+            elements: 0,
+        }
+    }
+
+    pub fn allocate(&mut self, object: HeapObject) -> HeapRef {
+        self.objects.push(object);
+        HeapRef(self.objects.len() - 1)
+    }
+
+    /// Accounts for `count` new elements, `false` if they exceed
+    /// `MAX_HEAP_ELEMENTS`.
+    pub fn reserve_elements(&mut self, count: usize) -> bool {
+        match self.elements.checked_add(count) {
+            Some(total) if total <= MAX_HEAP_ELEMENTS => {
+                self.elements = total;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn get(&self, handle: HeapRef) -> Option<&HeapObject> {
+        self.objects.get(handle.0)
+    }
+
+    pub fn get_mut(&mut self, handle: HeapRef) -> Option<&mut HeapObject> {
+        self.objects.get_mut(handle.0)
+    }
+}
+
+impl HeapRef {
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
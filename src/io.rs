@@ -0,0 +1,136 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Stdin, Write};
+use std::path::Path;
+use std::rc::Rc;
+
+/// Destination of the text written by output instructions such as `PRINT`.
+pub trait OutputSink {
+    fn write_str(&mut self, text: &str) -> io::Result<()>;
+}
+
+/// Discards the output, used by `State` until a sink is set.
+pub struct NullSink;
+
+impl OutputSink for NullSink {
+    fn write_str(&mut self, _text: &str) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+pub struct StdoutSink;
+
+impl OutputSink for StdoutSink {
+    fn write_str(&mut self, text: &str) -> io::Result<()> {
+        let mut stdout = io::stdout();
+        stdout.write_all(text.as_bytes())?;
+        stdout.flush()
+    }
+}
+
+pub struct StderrSink;
+
+impl OutputSink for StderrSink {
+    fn write_str(&mut self, text: &str) -> io::Result<()> {
+        io::stderr().write_all(text.as_bytes())
+    }
+}
+
+/// Collects the output in memory. Clones share the same buffer, so a clone can
+/// be handed to `State` and the other one inspected afterwards.
+#[derive(Debug, Clone, Default)]
+pub struct CaptureSink {
+    buffer: Rc<RefCell<Vec<u8>>>,
+}
+
+impl CaptureSink {
+    pub fn new() -> Self {
+        CaptureSink::default()
+    }
+
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.buffer.borrow()).into_owned()
+    }
+}
+
+impl OutputSink for CaptureSink {
+    fn write_str(&mut self, text: &str) -> io::Result<()> {
+        self.buffer.borrow_mut().extend_from_slice(text.as_bytes());
+        Ok(())
+    }
+}
+
+/// Supplier of the integers read by the `INPUT` instruction.
+pub trait InputSource {
+    /// Returns `Ok(None)` when there is no more input.
+    fn next_int(&mut self) -> io::Result<Option<i32>>;
+}
+
+/// Input taken from a Rust vector, mostly useful for tests.
+#[derive(Debug, Default)]
+pub struct VecSource {
+    values: VecDeque<i32>,
+}
+
+impl VecSource {
+    pub fn new(values: Vec<i32>) -> Self {
+        VecSource {
+            values: values.into(),
+        }
+    }
+}
+
+impl InputSource for VecSource {
+    fn next_int(&mut self) -> io::Result<Option<i32>> {
+        Ok(self.values.pop_front())
+    }
+}
+
+/// Reads whitespace-separated integers from any buffered reader.
+pub struct ReaderSource<R: BufRead> {
+    reader: R,
+    tokens: VecDeque<String>,
+}
+
+pub type StdinSource = ReaderSource<BufReader<Stdin>>;
+pub type FileSource = ReaderSource<BufReader<File>>;
+
+impl<R: BufRead> ReaderSource<R> {
+    pub fn new(reader: R) -> Self {
+        ReaderSource {
+            reader,
+            tokens: VecDeque::new(),
+        }
+    }
+}
+
+impl StdinSource {
+    pub fn stdin() -> Self {
+        ReaderSource::new(BufReader::new(io::stdin()))
+    }
+}
+
+impl FileSource {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(ReaderSource::new(BufReader::new(File::open(path)?)))
+    }
+}
+
+impl<R: BufRead> InputSource for ReaderSource<R> {
+    fn next_int(&mut self) -> io::Result<Option<i32>> {
+        while self.tokens.is_empty() {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            self.tokens
+                .extend(line.split_whitespace().map(str::to_owned));
+        }
+        let token = self.tokens.pop_front().unwrap();
+        token
+            .parse()
+            .map(Some)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, token))
+    }
+}
@@ -1,6 +1,17 @@
+pub mod bigint;
 pub mod bytecode;
+pub mod clock;
 pub mod commands;
+pub mod decimal;
 pub mod errors;
+pub mod heap;
+pub mod io;
+pub mod native;
 pub mod parser;
+pub mod rng;
+pub mod sandbox;
 pub mod simpleloop;
+pub mod span;
 pub mod state;
+pub mod tokenizer;
+pub mod value;
@@ -1,8 +1,14 @@
 use clap::{Arg, Command};
 use interpreter::bytecode::ByteCode;
+use interpreter::decimal::RoundingMode;
+use interpreter::io::{FileSource, StdoutSink};
 use std::error::Error;
 
-use interpreter::{parser::*, simpleloop::SimpleLoop, state::State};
+use interpreter::{
+    parser::*,
+    simpleloop::SimpleLoop,
+    state::{State, DEFAULT_LINEAR_MEMORY_SIZE},
+};
 
 fn main() -> Result<(), Box<dyn Error>> {
     let matches = Command::new("Bytecode interpreter")
@@ -19,6 +25,58 @@ fn main() -> Result<(), Box<dyn Error>> {
                 .takes_value(true)
                 .help("Input file with code"),
         )
+        .arg(
+            Arg::new("bigint")
+                .long("bigint")
+                .help("Run integer arithmetic with arbitrary precision"),
+        )
+        .arg(
+            Arg::new("scale")
+                .long("scale")
+                .takes_value(true)
+                .help("Number of fractional digits kept by decimal commands"),
+        )
+        .arg(
+            Arg::new("rounding")
+                .long("rounding")
+                .takes_value(true)
+                .possible_values(["HALF_UP", "HALF_EVEN", "DOWN", "UP", "FLOOR", "CEILING"])
+                .help("Default rounding mode of decimal commands"),
+        )
+        .arg(
+            Arg::new("memory-size")
+                .long("memory-size")
+                .takes_value(true)
+                .help("Size of the linear memory in bytes"),
+        )
+        .arg(
+            Arg::new("input")
+                .long("input")
+                .takes_value(true)
+                .help("File with integers read by INPUT instruction, stdin by default"),
+        )
+        .arg(
+            Arg::new("seed")
+                .long("seed")
+                .takes_value(true)
+                .help("Seed of the random number generator used by RAND and RAND_RANGE"),
+        )
+        .arg(
+            Arg::new("strip-asserts")
+                .long("strip-asserts")
+                .help("Skip the checks of ASSERT and ASSERT_EQ, their operands are still popped"),
+        )
+        .arg(
+            Arg::new("file-root")
+                .long("file-root")
+                .takes_value(true)
+                .help("Directory FILE_READ_INT and FILE_APPEND are allowed to access"),
+        )
+        .arg(
+            Arg::new("strict")
+                .long("strict")
+                .help("Reject instructions with missing, extra or wrongly typed arguments"),
+        )
         .get_matches();
 
     let file = matches
@@ -27,12 +85,58 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     let input = std::fs::read_to_string(file)?;
     println!("Code:\n{}", input);
-    let result = parse(input).unwrap();
+    let options = ParserOptions {
+        strip_asserts: matches.is_present("strip-asserts"),
+        file_name: Some(file.to_owned()),
+        strict: matches.is_present("strict"),
+        big_int: matches.is_present("bigint"),
+    };
+    let result = match parse_with_options(input, &options) {
+        Ok(code) => code,
+        Err(errors) => {
+            for error in &errors {
+                eprintln!("{}\n", error.render());
+            }
+            std::process::exit(1);
+        }
+    };
     let simpleloop = SimpleLoop::new();
-    let state: State = State::new(Some(simpleloop));
+    let memory_size = match matches.value_of("memory-size") {
+        Some(size) => size.parse()?,
+        None => DEFAULT_LINEAR_MEMORY_SIZE,
+    };
+    let mut state: State = State::with_linear_memory(Some(simpleloop), memory_size);
+    state.set_output_sink(Box::new(StdoutSink));
+    state.set_big_int_mode(matches.is_present("bigint"));
+    if let Some(seed) = matches.value_of("seed") {
+        state.set_seed(seed.parse()?);
+    }
+    if let Some(input) = matches.value_of("input") {
+        state.set_input_source(Box::new(FileSource::open(input)?));
+    }
+    if let Some(scale) = matches.value_of("scale") {
+        state.set_decimal_scale(scale.parse()?);
+    }
+    if let Some(rounding) = matches.value_of("rounding") {
+        state.set_rounding_mode(rounding.parse::<RoundingMode>().unwrap());
+    }
     let mut interpreter = ByteCode::new(state, result);
-    let result = interpreter.run()?;
+    if let Some(root) = matches.value_of("file-root") {
+        interpreter.grant_file_access(root);
+    }
+    let mut result = match interpreter.run_values() {
+        Ok(values) => values,
+        Err(error) => {
+            eprintln!("{}", error.render());
+            std::process::exit(1);
+        }
+    };
 
-    println!("Result: {:?}", result);
+    // A single value is shown like the result of ByteCode::run
+    if result.len() > 1 {
+        println!("Result: {:?}", result);
+    } else {
+        println!("Result: {:?}", result.pop());
+    }
     Ok(())
 }
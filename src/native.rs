@@ -0,0 +1,46 @@
+use crate::value::Value;
+use std::collections::HashMap;
+
+/// Host function callable from bytecode with `CALL_NATIVE`. It receives the
+/// arguments in the order they were pushed and returns values to push back.
+pub type NativeFunction = Box<dyn Fn(&[Value]) -> Result<Vec<Value>, String>>;
+
+pub struct NativeEntry {
+    arity: usize,
+    function: NativeFunction,
+}
+
+impl NativeEntry {
+    pub fn get_arity(&self) -> usize {
+        self.arity
+    }
+
+    pub fn call(&self, arguments: &[Value]) -> Result<Vec<Value>, String> {
+        (self.function)(arguments)
+    }
+}
+
+#[derive(Default)]
+pub struct NativeRegistry {
+    functions: HashMap<String, NativeEntry>,
+}
+
+impl NativeRegistry {
+    pub fn new() -> Self {
+        NativeRegistry::default()
+    }
+
+    /// Registers `function` taking `arity` arguments, replacing any function
+    /// previously registered under `name`.
+    pub fn register(&mut self, name: String, arity: usize, function: NativeFunction) {
+        self.functions.insert(name, NativeEntry { arity, function });
+    }
+
+    pub fn get(&self, name: &str) -> Option<&NativeEntry> {
+        self.functions.get(name)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.functions.contains_key(name)
+    }
+}
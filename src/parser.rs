@@ -1,89 +1,609 @@
-use crate::{bytecode::CodeType, commands::*, errors::*};
+use crate::{
+    bigint::BigInt,
+    bytecode::CodeType,
+    commands::*,
+    decimal::{Decimal, RoundingMode},
+    errors::*,
+    span::Span,
+    tokenizer::{is_identifier, tokenize, Token, TokenizeError},
+};
+use std::collections::HashMap;
+use std::ops::Range;
 
 type ParseResult = Result<Option<Box<dyn Command>>, ParserError>;
 
-pub fn parse(input: String) -> Result<CodeType, ParserError> {
-    if input.len() == 0 {
-        return Err(NoCodeError)?;
+/// Values of names defined with `.const`. They exist only while parsing and
+/// are unrelated to the variables of `State`.
+type Constants = HashMap<String, BigInt>;
+
+const CONST_DIRECTIVE: &str = ".const";
+
+#[derive(Debug, Default, Clone)]
+pub struct ParserOptions {
+    /// Skip the checks of `ASSERT` and `ASSERT_EQ`, e.g. for production runs.
+    /// Their operands are still popped from the stack.
+    pub strip_asserts: bool,
+    /// Name of the source shown in diagnostics, `<input>` if not set.
+    pub file_name: Option<String>,
+    /// Reject instructions with missing, extra or wrongly typed arguments
+    /// instead of ignoring extra arguments.
+    pub strict: bool,
+    /// Load integer literals out of range of i32 with `LOAD_VAL` as `BigInt`
+    /// instead of rejecting them.
+    pub big_int: bool,
+}
+
+const DEFAULT_FILE_NAME: &str = "<input>";
+
+pub fn parse(input: String) -> Result<CodeType, Vec<ParserError>> {
+    parse_with_options(input, &ParserOptions::default())
+}
+
+/// Parses the whole input and reports every erroneous line at once
+/// instead of stopping at the first one.
+pub fn parse_with_options(
+    input: String,
+    options: &ParserOptions,
+) -> Result<CodeType, Vec<ParserError>> {
+    if input.is_empty() {
+        return Err(vec![NoCodeError.into()]);
     }
     let mut code = CodeType::new();
+    let mut errors = Vec::new();
+    let mut constants = Constants::new();
     let lines = input.lines();
 
     for line_and_number in lines.enumerate() {
-        let result = treat_line_of_code(line_and_number)?;
-        match result {
-            Some(boxed_command) => code.push(boxed_command),
-            None => (),
+        match treat_line_of_code(line_and_number, options, &mut constants) {
+            Ok(Some(boxed_command)) => code.push(boxed_command),
+            Ok(None) => {}
+            Err(error) => errors.push(error),
         }
     }
 
-    Ok(code)
+    if errors.is_empty() {
+        Ok(code)
+    } else {
+        Err(errors)
+    }
 }
 
-fn treat_line_of_code(line_and_number: (usize, &str)) -> ParseResult {
+fn treat_line_of_code(
+    line_and_number: (usize, &str),
+    options: &ParserOptions,
+    constants: &mut Constants,
+) -> ParseResult {
     let (mut line_number, line) = line_and_number;
     line_number += 1;
     let line_with_number = format!("{}:     {}", line_number, line);
-    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let span_of = |bytes: Range<usize>| token_span(file_name(options), line_number, line, bytes);
+    let tokens = tokenize(line).map_err(|error| {
+        let (error, bytes) = match error {
+            TokenizeError::UnterminatedQuote(bytes) => (
+                ParserError::from(UnterminatedQuoteError::new(&line_with_number)),
+                bytes,
+            ),
+            TokenizeError::InvalidEscape(bytes) => (
+                ParserError::from(InvalidEscapeError::new(&line_with_number)),
+                bytes,
+            ),
+            TokenizeError::UnexpectedCharacter(bytes) => (
+                ParserError::from(UnexpectedCharacterError::new(&line_with_number)),
+                bytes,
+            ),
+        };
+        error.with_span(span_of(bytes))
+    })?;
 
     // Line is empty, so nothing to parse
-    if tokens.len() == 0 {
+    let instruction = match (tokens.first(), tokens.last()) {
+        (Some(first), Some(last)) => span_of(first.start..last.end()),
+        _ => return Ok(None),
+    };
+    if tokens[0].text == CONST_DIRECTIVE {
+        define_constant(&tokens, &line_with_number, constants, &span_of)?;
         return Ok(None);
     }
+    let command = parse_instruction(&tokens, line_with_number, options, constants, &span_of)?;
+    Ok(command
+        .map(|command| Box::new(SpannedCommand::new(command, instruction)) as Box<dyn Command>))
+}
 
-    let command_view = tokens.get(0).unwrap();
+fn parse_instruction<F: Fn(Range<usize>) -> Span>(
+    tokens: &[Token],
+    line_with_number: String,
+    options: &ParserOptions,
+    constants: &Constants,
+    span_of: &F,
+) -> ParseResult {
+    let error_label = line_with_number.clone();
+    let error_at = |token: &Token| {
+        ParserError::from(UnknownCommandError::new(&error_label)).with_span(span_of(token.bytes()))
+    };
+    let out_of_range = |token: &Token| {
+        ParserError::from(LiteralOutOfRangeError::new(&error_label))
+            .with_span(span_of(token.bytes()))
+    };
+    let command_view = tokens[0].text;
+    if options.strip_asserts {
+        match command_view {
+            "ASSERT" => return Ok(Some(Box::new(DropCommand::new(1, line_with_number)))),
+            "ASSERT_EQ" => return Ok(Some(Box::new(DropCommand::new(2, line_with_number)))),
+            _ => {}
+        }
+    }
+    let arity = instruction_arity(command_view);
+    if options.strict {
+        check_arguments(tokens, arity, &error_label, constants, span_of)?;
+    }
+    if command_view == "LOAD_STR" {
+        return match tokens.get(1) {
+            Some(literal) if literal.quote.is_some() => Ok(Some(Box::new(LoadValueCommand::new(
+                literal.value.clone(),
+                line_with_number,
+            )))),
+            Some(literal) => Err(error_at(literal)),
+            None => Err(error_at(&tokens[0])),
+        };
+    }
+    if command_view == "RAND_RANGE" {
+        let mut bounds = Vec::new();
+        for token in tokens[1..].iter().take(2) {
+            match int_argument(token, constants).map(|bound| bound.to_i32()) {
+                Some(Some(bound)) => bounds.push(bound),
+                Some(None) => return Err(out_of_range(token)),
+                None => break,
+            }
+        }
+        let arguments = || span_of(tokens[1].start..tokens[tokens.len() - 1].end());
+        return match bounds[..] {
+            [low, high] if low <= high => Ok(Some(Box::new(RandRangeCommand::new(
+                low,
+                high,
+                line_with_number,
+            )))),
+            [low, high] => Err(
+                ParserError::from(ReversedRangeError::new(low, high, &error_label))
+                    .with_span(arguments()),
+            ),
+            _ if tokens.len() > 1 => {
+                Err(ParserError::from(UnknownCommandError::new(&error_label))
+                    .with_span(arguments()))
+            }
+            _ => Err(error_at(&tokens[0])),
+        };
+    }
     let line_with_number_for_args = line_with_number.clone();
     // Check commands without args
-    let result: ParseResult = match *command_view {
+    let result: ParseResult = match command_view {
         "ADD" => Ok(Some(Box::new(AddCommand::new(line_with_number)))),
         "SUB" => Ok(Some(Box::new(SubCommand::new(line_with_number)))),
         "MUL" => Ok(Some(Box::new(MulCommand::new(line_with_number)))),
         "DIV" => Ok(Some(Box::new(DivCommand::new(line_with_number)))),
+        "FADD" => Ok(Some(Box::new(FloatAddCommand::new(line_with_number)))),
+        "FSUB" => Ok(Some(Box::new(FloatSubCommand::new(line_with_number)))),
+        "FMUL" => Ok(Some(Box::new(FloatMulCommand::new(line_with_number)))),
+        "FDIV" => Ok(Some(Box::new(FloatDivCommand::new(line_with_number)))),
+        "FSQRT" => Ok(Some(Box::new(FloatSqrtCommand::new(line_with_number)))),
+        "I2F" => Ok(Some(Box::new(IntToFloatCommand::new(line_with_number)))),
+        "F2I" => Ok(Some(Box::new(FloatToIntCommand::new(line_with_number)))),
+        "DADD" => Ok(Some(Box::new(DecimalAddCommand::new(line_with_number)))),
+        "DSUB" => Ok(Some(Box::new(DecimalSubCommand::new(line_with_number)))),
+        "DMUL" => Ok(Some(Box::new(DecimalMulCommand::new(
+            None,
+            line_with_number,
+        )))),
+        "DDIV" => Ok(Some(Box::new(DecimalDivCommand::new(
+            None,
+            line_with_number,
+        )))),
+        "CONCAT" => Ok(Some(Box::new(ConcatCommand::new(line_with_number)))),
+        "LEN" => Ok(Some(Box::new(LenCommand::new(line_with_number)))),
+        "SUBSTR" => Ok(Some(Box::new(SubstrCommand::new(line_with_number)))),
+        "STR_EQ" => Ok(Some(Box::new(StrEqCommand::new(line_with_number)))),
+        "TO_STR" => Ok(Some(Box::new(ToStrCommand::new(line_with_number)))),
+        "PARSE_INT" => Ok(Some(Box::new(ParseIntCommand::new(line_with_number)))),
+        "ARR_GET" => Ok(Some(Box::new(ArrayGetCommand::new(line_with_number)))),
+        "ARR_SET" => Ok(Some(Box::new(ArraySetCommand::new(line_with_number)))),
+        "ARR_LEN" => Ok(Some(Box::new(ArrayLenCommand::new(line_with_number)))),
+        "ARR_PUSH" => Ok(Some(Box::new(ArrayPushCommand::new(line_with_number)))),
+        "LOAD_ADDR" => Ok(Some(Box::new(LoadAddressCommand::new(line_with_number)))),
+        "STORE_ADDR" => Ok(Some(Box::new(StoreAddressCommand::new(line_with_number)))),
+        "MAP_NEW" => Ok(Some(Box::new(MapNewCommand::new(line_with_number)))),
+        "MAP_PUT" => Ok(Some(Box::new(MapPutCommand::new(line_with_number)))),
+        "MAP_GET" => Ok(Some(Box::new(MapGetCommand::new(line_with_number)))),
+        "MAP_HAS" => Ok(Some(Box::new(MapHasCommand::new(line_with_number)))),
+        "MAP_DEL" => Ok(Some(Box::new(MapDelCommand::new(line_with_number)))),
+        "PRINT" => Ok(Some(Box::new(PrintCommand::new(line_with_number)))),
+        "EMIT" => Ok(Some(Box::new(EmitCommand::new(line_with_number)))),
+        "INPUT" => Ok(Some(Box::new(InputCommand::new(line_with_number)))),
+        "RAND" => Ok(Some(Box::new(RandCommand::new(line_with_number)))),
+        "TIME" => Ok(Some(Box::new(TimeCommand::new(line_with_number)))),
+        "ASSERT" => Ok(Some(Box::new(AssertCommand::new(line_with_number)))),
+        "ASSERT_EQ" => Ok(Some(Box::new(AssertEqCommand::new(line_with_number)))),
+        "DUMP" => Ok(Some(Box::new(DumpCommand::new(line_with_number)))),
+        "BREAKPOINT" => Ok(Some(Box::new(BreakpointCommand::new(line_with_number)))),
         "RETURN" => Ok(Some(Box::new(ReturnCommand::new(line_with_number)))),
         "END_LOOP" => Ok(Some(Box::new(EndLoopCommand::new(line_with_number)))),
-        _ => Err(ParserError::from(UnknownCommandError::new(
-            &line_with_number,
-        ))),
+        _ => Err(error_at(&tokens[0])),
     };
 
-    let command_arg = tokens.get(1);
-    if command_arg.is_none() {
-        return result;
+    let takes_no_arguments = arity.is_some_and(|arity| arity.arguments.is_empty());
+    let command_arg = match tokens.get(1) {
+        Some(command_arg) if !takes_no_arguments => command_arg,
+        // Extra arguments of instructions without arguments are ignored
+        _ => return result,
+    };
+    if let Some(ArgumentKind::Name | ArgumentKind::Path | ArgumentKind::Native) =
+        arity.and_then(|arity| arity.arguments.first().copied())
+    {
+        return parse_named_instruction(
+            command_view,
+            command_arg.value.clone(),
+            line_with_number_for_args,
+        )
+        .map(Some)
+        .ok_or_else(|| error_at(&tokens[0]));
     }
-    let argument = *command_arg.unwrap();
-    let numerical_argument: Result<i32, _> = argument.parse();
-    if numerical_argument.is_ok() {
+    let argument = command_arg.text;
+    // Constants stand only for integer parameters, e.g. `DDIV UP` keeps the
+    // rounding mode even if a constant UP is defined
+    let literal = match arity.and_then(|arity| arity.arguments.first()) {
+        Some(ArgumentKind::Integer | ArgumentKind::Count) => int_argument(command_arg, constants),
+        _ => parse_int_literal(command_arg),
+    };
+    let numerical_argument = literal.as_ref().and_then(BigInt::to_i32);
+    if let (Some(literal), None) = (&literal, numerical_argument) {
+        match command_view {
+            "RETURN" | "NEW_ARRAY" | "LOOP" => return Err(out_of_range(command_arg)),
+            "LOAD_VAL" if !options.big_int => return Err(out_of_range(command_arg)),
+            "LOAD_FLOAT" => {
+                return Ok(Some(Box::new(LoadValueCommand::new(
+                    literal_to_float(literal),
+                    line_with_number_for_args,
+                ))))
+            }
+            "LOAD_DEC" => {
+                return match literal_to_decimal(literal) {
+                    Some(value) => Ok(Some(Box::new(LoadDecimalCommand::new(
+                        value,
+                        line_with_number_for_args,
+                    )))),
+                    None => Err(out_of_range(command_arg)),
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(argument) = numerical_argument {
         // Check commands with numerical arg
-        let argument = numerical_argument.unwrap();
-        let result: ParseResult = match *command_view {
+        match command_view {
             "LOAD_VAL" => Ok(Some(Box::new(LoadValueCommand::new(
                 argument,
                 line_with_number_for_args,
             )))),
-            "LOOP" => Ok(Some(Box::new(StartLoopCommand::new(
-                argument,
+            "LOAD_FLOAT" => Ok(Some(Box::new(LoadValueCommand::new(
+                argument as f64,
                 line_with_number_for_args,
             )))),
-            _ => Err(ParserError::from(UnknownCommandError::new(
-                &line_with_number_for_args,
-            ))),
-        };
-        return result;
-    } else {
-        // Check commands with string arg
-        let result: ParseResult = match *command_view {
-            "WRITE_VAR" => Ok(Some(Box::new(WriteVariableCommand::new(
-                argument.to_owned(),
+            "RETURN" if argument >= 0 => Ok(Some(Box::new(ReturnCommand::with_count(
+                argument as usize,
+                line_with_number_for_args,
+            )))),
+            "NEW_ARRAY" if argument >= 0 => Ok(Some(Box::new(NewArrayCommand::new(
+                argument as usize,
+                line_with_number_for_args,
+            )))),
+            "LOAD_DEC" => Ok(Some(Box::new(LoadDecimalCommand::new(
+                Decimal::from(argument),
                 line_with_number_for_args,
             )))),
-            "READ_VAR" => Ok(Some(Box::new(ReadVariableCommand::new(
-                argument.to_owned(),
+            "LOOP" => Ok(Some(Box::new(StartLoopCommand::new(
+                argument,
                 line_with_number_for_args,
             )))),
-            _ => Err(ParserError::from(UnknownCommandError::new(
-                &line_with_number_for_args,
-            ))),
+            // Count of values or elements can not be negative
+            "RETURN" | "NEW_ARRAY" => Err(error_at(command_arg)),
+            _ => Err(error_at(&tokens[0])),
+        }
+    } else {
+        // Check commands with string arg
+        match command_view {
+            // Only with the big_int option a literal out of range of i32 gets here
+            "LOAD_VAL" => match literal {
+                Some(literal) => Ok(Some(Box::new(LoadValueCommand::new(
+                    literal,
+                    line_with_number_for_args,
+                )))),
+                None => Err(error_at(command_arg)),
+            },
+            "LOAD_DEC" => match argument.parse::<Decimal>() {
+                Ok(argument) => Ok(Some(Box::new(LoadDecimalCommand::new(
+                    argument,
+                    line_with_number_for_args,
+                )))),
+                Err(_) => Err(error_at(command_arg)),
+            },
+            "DMUL" | "DDIV" => match argument.parse::<RoundingMode>() {
+                Ok(rounding_mode) if command_view == "DMUL" => Ok(Some(Box::new(
+                    DecimalMulCommand::new(Some(rounding_mode), line_with_number_for_args),
+                ))),
+                Ok(rounding_mode) => Ok(Some(Box::new(DecimalDivCommand::new(
+                    Some(rounding_mode),
+                    line_with_number_for_args,
+                )))),
+                Err(_) => Err(error_at(command_arg)),
+            },
+            "LOAD_FLOAT" => match argument.parse::<f64>() {
+                Ok(argument) => Ok(Some(Box::new(LoadValueCommand::new(
+                    argument,
+                    line_with_number_for_args,
+                )))),
+                Err(_) => Err(error_at(command_arg)),
+            },
+            _ => Err(error_at(&tokens[0])),
+        }
+    }
+}
+
+/// Builds instructions whose argument is a variable name, a path or a
+/// native function name.
+fn parse_named_instruction(command: &str, name: String, line: String) -> Option<Box<dyn Command>> {
+    let command: Box<dyn Command> = match command {
+        "WRITE_VAR" => Box::new(WriteVariableCommand::new(name, line)),
+        "READ_VAR" => Box::new(ReadVariableCommand::new(name, line)),
+        "CONST_VAR" => Box::new(ConstVariableCommand::new(name, line)),
+        "DEL_VAR" => Box::new(DeleteVariableCommand::new(name, line)),
+        "HAS_VAR" => Box::new(HasVariableCommand::new(name, line)),
+        "FILE_READ_INT" => Box::new(FileReadIntCommand::new(name, line)),
+        "FILE_APPEND" => Box::new(FileAppendCommand::new(name, line)),
+        "CALL_NATIVE" => Box::new(CallNativeCommand::new(name, line)),
+        _ => return None,
+    };
+    Some(command)
+}
+
+/// Kind of value an instruction argument must have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArgumentKind {
+    Integer,
+    Count,
+    Float,
+    Decimal,
+    RoundingMode,
+    Name,
+    /// Name defined with `.const`, never quoted as it is not looked up otherwise.
+    ConstName,
+    Path,
+    Native,
+    Text,
+}
+
+impl ArgumentKind {
+    fn description(&self) -> &'static str {
+        match self {
+            ArgumentKind::Integer => "an integer",
+            ArgumentKind::Count => "a non-negative integer",
+            ArgumentKind::Float => "a floating-point number",
+            ArgumentKind::Decimal => "a decimal number",
+            ArgumentKind::RoundingMode => "a rounding mode",
+            ArgumentKind::Name => "a variable name",
+            ArgumentKind::ConstName => "a constant name",
+            ArgumentKind::Path => "a path",
+            ArgumentKind::Native => "a native function name",
+            ArgumentKind::Text => "a string literal",
+        }
+    }
+
+    fn accepts(&self, token: &Token, constants: &Constants) -> bool {
+        // Numbers and rounding modes are never quoted, except character literals
+        let bare = token.quote.is_none();
+        let text = token.text;
+        let integer = int_argument(token, constants);
+        let literal = parse_int_literal(token);
+        match self {
+            ArgumentKind::Integer => integer.is_some(),
+            ArgumentKind::Count => integer.is_some_and(|count| !count.is_negative()),
+            ArgumentKind::Float => literal.is_some() || bare && text.parse::<f64>().is_ok(),
+            ArgumentKind::Decimal => literal.is_some() || bare && text.parse::<Decimal>().is_ok(),
+            ArgumentKind::RoundingMode => bare && text.parse::<RoundingMode>().is_ok(),
+            ArgumentKind::Name => is_identifier(&token.value),
+            ArgumentKind::ConstName => bare && is_identifier(text),
+            ArgumentKind::Path | ArgumentKind::Native => true,
+            ArgumentKind::Text => !bare,
+        }
+    }
+}
+
+/// Arguments of an instruction, the first `required` of them are obligatory.
+#[derive(Debug, Clone, Copy)]
+struct Arity {
+    required: usize,
+    arguments: &'static [ArgumentKind],
+}
+
+impl Arity {
+    const fn new(required: usize, arguments: &'static [ArgumentKind]) -> Self {
+        Arity {
+            required,
+            arguments,
+        }
+    }
+
+    fn description(&self) -> String {
+        if self.required == self.arguments.len() {
+            self.required.to_string()
+        } else {
+            format!("{} to {}", self.required, self.arguments.len())
+        }
+    }
+}
+
+/// Arguments accepted by every instruction, `None` for unknown instructions.
+fn instruction_arity(command: &str) -> Option<Arity> {
+    use ArgumentKind::*;
+    let arity = match command {
+        "ADD" | "SUB" | "MUL" | "DIV" | "FADD" | "FSUB" | "FMUL" | "FDIV" | "FSQRT" | "I2F"
+        | "F2I" | "DADD" | "DSUB" | "CONCAT" | "LEN" | "SUBSTR" | "STR_EQ" | "TO_STR"
+        | "PARSE_INT" | "ARR_GET" | "ARR_SET" | "ARR_LEN" | "ARR_PUSH" | "LOAD_ADDR"
+        | "STORE_ADDR" | "MAP_NEW" | "MAP_PUT" | "MAP_GET" | "MAP_HAS" | "MAP_DEL" | "PRINT"
+        | "EMIT" | "INPUT" | "RAND" | "TIME" | "ASSERT" | "ASSERT_EQ" | "DUMP" | "BREAKPOINT"
+        | "END_LOOP" => Arity::new(0, &[]),
+        "DMUL" | "DDIV" => Arity::new(0, &[RoundingMode]),
+        "RETURN" => Arity::new(0, &[Count]),
+        "LOAD_VAL" | "LOOP" => Arity::new(1, &[Integer]),
+        "LOAD_STR" => Arity::new(1, &[Text]),
+        "LOAD_FLOAT" => Arity::new(1, &[Float]),
+        "LOAD_DEC" => Arity::new(1, &[Decimal]),
+        "NEW_ARRAY" => Arity::new(1, &[Count]),
+        "RAND_RANGE" => Arity::new(2, &[Integer, Integer]),
+        "WRITE_VAR" | "READ_VAR" | "CONST_VAR" | "DEL_VAR" | "HAS_VAR" => Arity::new(1, &[Name]),
+        "FILE_READ_INT" | "FILE_APPEND" => Arity::new(1, &[Path]),
+        CONST_DIRECTIVE => Arity::new(2, &[ConstName, Integer]),
+        "CALL_NATIVE" => Arity::new(1, &[Native]),
+        _ => return None,
+    };
+    Some(arity)
+}
+
+/// Strict mode check of the number and the kinds of arguments of the
+/// instruction in `tokens`.
+fn check_arguments<F: Fn(Range<usize>) -> Span>(
+    tokens: &[Token],
+    arity: Option<Arity>,
+    label: &str,
+    constants: &Constants,
+    span_of: &F,
+) -> Result<(), ParserError> {
+    let (command, arguments) = (tokens[0].text, &tokens[1..]);
+    let arity = match arity {
+        Some(arity) => arity,
+        None => {
+            return Err(ParserError::from(UnknownCommandError::new(label))
+                .with_span(span_of(tokens[0].bytes())))
+        }
+    };
+    let count_error = || {
+        ParserError::from(ArgumentCountError::new(
+            command,
+            arity.description(),
+            arguments.len(),
+            label,
+        ))
+    };
+    if arguments.len() < arity.required {
+        return Err(count_error().with_span(span_of(tokens[0].bytes())));
+    }
+    if arguments.len() > arity.arguments.len() {
+        let extra = &arguments[arity.arguments.len()..];
+        let span = span_of(extra[0].start..extra[extra.len() - 1].end());
+        return Err(count_error().with_span(span));
+    }
+    for (argument, kind) in arguments.iter().zip(arity.arguments) {
+        if kind.accepts(argument, constants) {
+            continue;
+        }
+        let error = if *kind == ArgumentKind::Name {
+            ParserError::from(InvalidIdentifierError::new(&argument.value, label))
+        } else {
+            ParserError::from(ArgumentTypeError::new(
+                command,
+                kind.description(),
+                argument.text,
+                label,
+            ))
         };
-        return result;
+        return Err(error.with_span(span_of(argument.bytes())));
+    }
+    Ok(())
+}
+
+/// Handles `.const NAME value`, `value` is an integer literal or a name
+/// defined before.
+fn define_constant<F: Fn(Range<usize>) -> Span>(
+    tokens: &[Token],
+    label: &str,
+    constants: &mut Constants,
+    span_of: &F,
+) -> Result<(), ParserError> {
+    let directive = tokens[0].text;
+    let arity = instruction_arity(directive);
+    check_arguments(tokens, arity, label, constants, span_of)?;
+    let name = &tokens[1];
+    if constants.contains_key(&name.value) {
+        let error = ConstRedefinitionError::new(&name.value, label);
+        return Err(ParserError::from(error).with_span(span_of(name.bytes())));
+    }
+    // The value was checked to be an integer above
+    if let Some(value) = int_argument(&tokens[2], constants) {
+        constants.insert(name.value.clone(), value);
+    }
+    Ok(())
+}
+
+/// Value of an integer argument, a literal or a name defined with `.const`.
+fn int_argument(token: &Token, constants: &Constants) -> Option<BigInt> {
+    match constants.get(token.text) {
+        Some(value) if token.quote.is_none() => Some(value.clone()),
+        _ => parse_int_literal(token),
+    }
+}
+
+/// Parses an integer literal such as `42`, `-0xFF`, `0b1010`, `0o17`,
+/// `1_000_000` or a character in single quotes like `'A'`. The value may be
+/// out of range of i32, it is up to the caller to check it.
+fn parse_int_literal(token: &Token) -> Option<BigInt> {
+    match token.quote {
+        Some('\'') => {
+            let mut chars = token.value.chars();
+            return match (chars.next(), chars.next()) {
+                (Some(symbol), None) => Some(BigInt::from(symbol as i32)),
+                _ => None,
+            };
+        }
+        Some(_) => return None,
+        None => {}
+    }
+    let (negative, literal) = match token.text.strip_prefix('-') {
+        Some(literal) => (true, literal),
+        None => (false, token.text.strip_prefix('+').unwrap_or(token.text)),
+    };
+    let (radix, digits) = match literal.get(..2) {
+        Some("0x" | "0X") => (16, &literal[2..]),
+        Some("0o" | "0O") => (8, &literal[2..]),
+        Some("0b" | "0B") => (2, &literal[2..]),
+        _ => (10, literal),
+    };
+    // Underscores may only separate digits
+    if digits.starts_with('_') || digits.ends_with('_') || digits.contains("__") {
+        return None;
     }
+    let digits: String = digits.chars().filter(|symbol| *symbol != '_').collect();
+    let magnitude = BigInt::from_str_radix(&digits, radix).ok()?;
+    Some(if negative { -&magnitude } else { magnitude })
+}
+
+/// Integer literal as f64, large values lose precision like any f64.
+fn literal_to_float(literal: &BigInt) -> f64 {
+    literal.to_string().parse().unwrap_or(f64::NAN)
+}
+
+/// Integer literal as decimal, `None` if it does not fit into the mantissa.
+fn literal_to_decimal(literal: &BigInt) -> Option<Decimal> {
+    literal.to_i64().map(|mantissa| Decimal::new(mantissa, 0))
+}
+
+fn file_name(options: &ParserOptions) -> &str {
+    options.file_name.as_deref().unwrap_or(DEFAULT_FILE_NAME)
+}
+
+/// Span of the byte range `bytes` of `source`.
+fn token_span(file: &str, line_number: usize, source: &str, bytes: Range<usize>) -> Span {
+    let start = source[..bytes.start].chars().count();
+    Span::new(
+        file,
+        line_number,
+        start..start + source[bytes].chars().count(),
+        source,
+    )
 }
@@ -0,0 +1,38 @@
+/// Small deterministic pseudo-random number generator (SplitMix64).
+/// The same seed always produces the same sequence.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut value = self.state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        value ^ (value >> 31)
+    }
+
+    /// Returns a value in `0..=i32::MAX`.
+    pub fn next_i32(&mut self) -> i32 {
+        (self.next_u64() >> 33) as i32
+    }
+
+    /// Returns a uniformly distributed value in `low..=high`.
+    pub fn range(&mut self, low: i32, high: i32) -> i32 {
+        let span = (high as i64 - low as i64 + 1) as u64;
+        // Reject values from the incomplete last span to avoid modulo bias
+        let limit = u64::MAX - u64::MAX % span;
+        loop {
+            let value = self.next_u64();
+            if value < limit {
+                return (low as i64 + (value % span) as i64) as i32;
+            }
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use std::fs::{self, File, Metadata, OpenOptions};
+use std::io::{self, ErrorKind, Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+/// Directory granted to a program for file instructions. Paths used by the
+/// program are relative to the root and may not leave it.
+#[derive(Debug, Clone)]
+pub struct FileSandbox {
+    root: PathBuf,
+}
+
+/// Reason a file operation inside the sandbox failed.
+#[derive(Debug)]
+pub enum SandboxError {
+    /// The path leads outside of the root or through a symlink.
+    PermissionDenied,
+    Io(io::Error),
+}
+
+impl FileSandbox {
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+        FileSandbox { root: root.into() }
+    }
+
+    pub fn read_to_string(&self, path: &str) -> Result<String, SandboxError> {
+        let (root, candidate) = self.resolve(path)?;
+        let mut file = File::open(&candidate).map_err(SandboxError::Io)?;
+        verify_opened(&root, &candidate, &file)?;
+        let mut content = String::new();
+        file.read_to_string(&mut content)
+            .map_err(SandboxError::Io)?;
+        Ok(content)
+    }
+
+    /// Appends `text` to the file, creating it if it does not exist.
+    pub fn append(&self, path: &str, text: &str) -> Result<(), SandboxError> {
+        let (root, candidate) = self.resolve(path)?;
+        // A new file is created exclusively, which never follows a symlink
+        let file = match OpenOptions::new().append(true).open(&candidate) {
+            Err(error) if error.kind() == ErrorKind::NotFound => OpenOptions::new()
+                .append(true)
+                .create_new(true)
+                .open(&candidate),
+            result => result,
+        };
+        let mut file = file.map_err(SandboxError::Io)?;
+        verify_opened(&root, &candidate, &file)?;
+        file.write_all(text.as_bytes()).map_err(SandboxError::Io)
+    }
+
+    /// Returns the canonical root and the location of `path` inside it.
+    /// Symlinks are rejected, even dangling ones, as they may lead outside.
+    fn resolve(&self, path: &str) -> Result<(PathBuf, PathBuf), SandboxError> {
+        let relative = Path::new(path);
+        let is_plain = relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+        if path.is_empty() || !is_plain {
+            return Err(SandboxError::PermissionDenied);
+        }
+        let root = self
+            .root
+            .canonicalize()
+            .map_err(|_| SandboxError::PermissionDenied)?;
+        let mut candidate = root.clone();
+        for component in relative.components() {
+            if let Component::Normal(part) = component {
+                candidate.push(part);
+                let is_symlink = fs::symlink_metadata(&candidate)
+                    .is_ok_and(|metadata| metadata.file_type().is_symlink());
+                if is_symlink {
+                    return Err(SandboxError::PermissionDenied);
+                }
+            }
+        }
+        Ok((root, candidate))
+    }
+}
+
+/// Checks that the opened file is the one inside the root, in case the path
+/// was replaced between `resolve` and opening.
+fn verify_opened(root: &Path, candidate: &Path, file: &File) -> Result<(), SandboxError> {
+    let real = candidate
+        .canonicalize()
+        .map_err(|_| SandboxError::PermissionDenied)?;
+    let expected = fs::metadata(&real).map_err(|_| SandboxError::PermissionDenied)?;
+    let opened = file.metadata().map_err(SandboxError::Io)?;
+    if real.starts_with(root) && is_same_file(&expected, &opened) {
+        Ok(())
+    } else {
+        Err(SandboxError::PermissionDenied)
+    }
+}
+
+#[cfg(unix)]
+fn is_same_file(first: &Metadata, second: &Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    first.dev() == second.dev() && first.ino() == second.ino()
+}
+
+/// The identity of an opened file can not be checked, so file instructions
+/// are always denied.
+#[cfg(not(unix))]
+fn is_same_file(_first: &Metadata, _second: &Metadata) -> bool {
+    false
+}
@@ -4,6 +4,12 @@ pub struct SimpleLoop {
     counter: Option<i32>,
 }
 
+impl Default for SimpleLoop {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SimpleLoop {
     pub fn new() -> Self {
         SimpleLoop {
@@ -27,9 +33,8 @@ impl SimpleLoop {
     }
 
     pub fn dec_counter(&mut self) {
-        match self.counter.as_mut() {
-            Some(counter) => *counter -= 1,
-            None => (),
+        if let Some(counter) = self.counter.as_mut() {
+            *counter -= 1;
         }
     }
 
@@ -0,0 +1,95 @@
+use std::fmt;
+use std::ops::Range;
+
+/// Location of a piece of source code used to point diagnostics at the
+/// offending token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    file: String,
+    line: usize,
+    columns: Range<usize>,
+    source: String,
+}
+
+impl Span {
+    /// `line` is 1-based, `columns` are 0-based character offsets in `source`,
+    /// the text of the whole line.
+    pub fn new(file: &str, line: usize, columns: Range<usize>, source: &str) -> Self {
+        Span {
+            file: file.to_owned(),
+            line,
+            columns,
+            source: source.to_owned(),
+        }
+    }
+
+    pub fn file(&self) -> &str {
+        &self.file
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn columns(&self) -> Range<usize> {
+        self.columns.clone()
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Renders `message` followed by the source line with the span underlined:
+    ///
+    /// ```text
+    /// error: UknownCommandError: The command is unkonwn or syntax is wrong
+    ///  --> code.txt:2:10
+    ///   |
+    /// 2 | LOAD_VAL one
+    ///   |          ^^^
+    /// ```
+    pub fn render(&self, message: &str) -> String {
+        let number = self.line.to_string();
+        let gutter = " ".repeat(number.len());
+        let width = self.columns.len().max(1);
+        format!(
+            "error: {}\n{}--> {}\n{} |\n{} | {}\n{} | {}{}",
+            message,
+            gutter,
+            self,
+            gutter,
+            number,
+            self.source,
+            gutter,
+            " ".repeat(self.columns.start),
+            "^".repeat(width)
+        )
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "{}:{}:{}",
+            self.file,
+            self.line,
+            self.columns.start + 1
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let span = Span::new("code.txt", 12, 9..12, "LOAD_VAL one");
+        assert_eq!(span.to_string(), "code.txt:12:10");
+        assert_eq!(
+            span.render("bad argument"),
+            "error: bad argument\n  --> code.txt:12:10\n   |\n12 | LOAD_VAL one\n   |          ^^^"
+        );
+    }
+}
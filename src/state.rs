@@ -1,54 +1,262 @@
+use crate::clock::{Clock, SystemClock};
+use crate::decimal::RoundingMode;
 use crate::errors::*;
+use crate::heap::{Heap, HeapObject, HeapRef, MapKey};
+use crate::io::{InputSource, NullSink, OutputSink, StderrSink, StdinSource};
+use crate::native::NativeRegistry;
+use crate::rng::Rng;
+use crate::sandbox::FileSandbox;
 use crate::simpleloop::SimpleLoop;
-use std::collections::HashMap;
+use crate::value::Value;
+use std::collections::{HashMap, HashSet};
 
 const INIT_STACK_SIZE: usize = 2000;
+const DEFAULT_DECIMAL_SCALE: u32 = 2;
+pub const DEFAULT_SEED: u64 = 0;
+/// Default size of the linear memory in bytes, one WebAssembly page.
+pub const DEFAULT_LINEAR_MEMORY_SIZE: usize = 65536;
+const WORD_SIZE: usize = std::mem::size_of::<i32>();
+
+/// Host callback invoked by `BREAKPOINT` with the state and the line of the
+/// instruction.
+pub type BreakpointHandler = Box<dyn FnMut(&mut State, &str)>;
 
 pub struct State {
-    stack: Vec<i32>,
-    memory: HashMap<String, i32>,
+    stack: Vec<Value>,
+    memory: HashMap<String, Value>,
+    constants: HashSet<String>,
+    heap: Heap,
+    linear_memory: Vec<u8>,
     command_pointer: usize,
     ret: bool,
+    return_values: Option<Vec<Value>>,
     loop_instance: Option<SimpleLoop>,
+    output: Box<dyn OutputSink>,
+    input: Box<dyn InputSource>,
+    debug_output: Box<dyn OutputSink>,
+    breakpoint_handler: Option<BreakpointHandler>,
+    rng: Rng,
+    clock: Box<dyn Clock>,
+    natives: NativeRegistry,
+    file_sandbox: Option<FileSandbox>,
+    big_int_mode: bool,
+    decimal_scale: u32,
+    rounding_mode: RoundingMode,
 }
 
 impl State {
     pub fn new(loop_instance: Option<SimpleLoop>) -> Self {
+        State::with_linear_memory(loop_instance, DEFAULT_LINEAR_MEMORY_SIZE)
+    }
+
+    /// Creates a state with `linear_memory_size` bytes of zeroed linear memory.
+    pub fn with_linear_memory(
+        loop_instance: Option<SimpleLoop>,
+        linear_memory_size: usize,
+    ) -> Self {
         State {
             stack: Vec::with_capacity(INIT_STACK_SIZE),
             memory: HashMap::new(),
+            constants: HashSet::new(),
+            heap: Heap::new(),
+            linear_memory: vec![0; linear_memory_size],
             command_pointer: 0,
             ret: false,
+            return_values: None,
             loop_instance,
+            output: Box::new(NullSink),
+            input: Box::new(StdinSource::stdin()),
+            debug_output: Box::new(StderrSink),
+            breakpoint_handler: None,
+            rng: Rng::new(DEFAULT_SEED),
+            clock: Box::new(SystemClock),
+            natives: NativeRegistry::new(),
+            file_sandbox: None,
+            big_int_mode: false,
+            decimal_scale: DEFAULT_DECIMAL_SCALE,
+            rounding_mode: RoundingMode::HalfEven,
         }
     }
 
-    pub fn push_to_stack(&mut self, value: i32) {
+    pub fn push_to_stack(&mut self, value: Value) {
         self.stack.push(value);
     }
 
-    pub fn pop_from_stack<'a>(&mut self, message: &'a str) -> Result<i32, EmptyStackError<'a>> {
+    pub fn pop_from_stack<'a>(&mut self, message: &'a str) -> Result<Value, EmptyStackError<'a>> {
         self.stack.pop().ok_or(EmptyStackError::new(message))
     }
 
-    pub fn pop_from_stack_option(&mut self) -> Option<i32> {
+    pub fn pop_from_stack_option(&mut self) -> Option<Value> {
         self.stack.pop()
     }
 
-    pub fn memory_insert(&mut self, variable_name: String, variable: i32) {
+    /// Pops `count` values at once, keeping the order in which they were pushed.
+    pub fn pop_many_from_stack<'a>(
+        &mut self,
+        count: usize,
+        message: &'a str,
+    ) -> Result<Vec<Value>, EmptyStackError<'a>> {
+        if self.stack.len() < count {
+            return Err(EmptyStackError::new(message));
+        }
+        Ok(self.stack.split_off(self.stack.len() - count))
+    }
+
+    pub fn memory_insert<'a>(
+        &mut self,
+        variable_name: String,
+        variable: Value,
+        message: &'a str,
+    ) -> Result<(), ConstReassignmentError<'a>> {
+        if self.constants.contains(&variable_name) {
+            return Err(ConstReassignmentError::new(message));
+        }
         self.memory.insert(variable_name, variable);
+        Ok(())
+    }
+
+    /// Binds a variable which can be neither overwritten nor deleted later.
+    pub fn memory_insert_const<'a>(
+        &mut self,
+        variable_name: String,
+        variable: Value,
+        message: &'a str,
+    ) -> Result<(), ConstReassignmentError<'a>> {
+        self.memory_insert(variable_name.clone(), variable, message)?;
+        self.constants.insert(variable_name);
+        Ok(())
+    }
+
+    pub fn memory_remove(
+        &mut self,
+        variable_name: &String,
+        message: &str,
+    ) -> Result<(), RuntimeError> {
+        if self.constants.contains(variable_name) {
+            return Err(ConstReassignmentError::new(message))?;
+        }
+        self.memory
+            .remove(variable_name)
+            .ok_or(UnknownVariableLoadingError::new(message))?;
+        Ok(())
+    }
+
+    pub fn memory_contains(&self, variable_name: &String) -> bool {
+        self.memory.contains_key(variable_name)
     }
 
     pub fn memory_get<'a>(
         &self,
         variable_name: &String,
         message: &'a str,
-    ) -> Result<i32, UnknownVariableLoadingError<'a>> {
+    ) -> Result<Value, UnknownVariableLoadingError<'a>> {
         let value = self
             .memory
             .get(variable_name)
             .ok_or(UnknownVariableLoadingError::new(message))?;
-        Ok(*value)
+        Ok(value.clone())
+    }
+
+    /// Allocates an array of `size` zeros within the heap limit.
+    pub fn heap_allocate_array<'a>(
+        &mut self,
+        size: usize,
+        message: &'a str,
+    ) -> Result<HeapRef, HeapLimitError<'a>> {
+        self.heap_reserve_elements(size, message)?;
+        Ok(self
+            .heap
+            .allocate(HeapObject::Array(vec![Value::Int(0); size])))
+    }
+
+    /// Allocates an empty map within the heap limit.
+    pub fn heap_allocate_map<'a>(
+        &mut self,
+        message: &'a str,
+    ) -> Result<HeapRef, HeapLimitError<'a>> {
+        self.heap_reserve_elements(1, message)?;
+        Ok(self.heap.allocate(HeapObject::Map(HashMap::new())))
+    }
+
+    /// Accounts for `count` elements added to an existing array or map.
+    pub fn heap_reserve_elements<'a>(
+        &mut self,
+        count: usize,
+        message: &'a str,
+    ) -> Result<(), HeapLimitError<'a>> {
+        if self.heap.reserve_elements(count) {
+            Ok(())
+        } else {
+            Err(HeapLimitError::new(message))
+        }
+    }
+
+    pub fn heap_get_array_mut(
+        &mut self,
+        handle: HeapRef,
+        message: &str,
+    ) -> Result<&mut Vec<Value>, RuntimeError> {
+        match self.heap.get_mut(handle) {
+            Some(HeapObject::Array(array)) => Ok(array),
+            Some(other) => Err(TypeMismatchError::new("Array", other.type_name(), message))?,
+            None => Err(IndexOutOfBoundsError::new(
+                handle.index() as i32,
+                0,
+                message,
+            ))?,
+        }
+    }
+
+    pub fn heap_get_map_mut(
+        &mut self,
+        handle: HeapRef,
+        message: &str,
+    ) -> Result<&mut HashMap<MapKey, Value>, RuntimeError> {
+        match self.heap.get_mut(handle) {
+            Some(HeapObject::Map(map)) => Ok(map),
+            Some(other) => Err(TypeMismatchError::new("Map", other.type_name(), message))?,
+            None => Err(IndexOutOfBoundsError::new(
+                handle.index() as i32,
+                0,
+                message,
+            ))?,
+        }
+    }
+
+    /// Checks that a word at `address` fits into the linear memory and returns
+    /// its byte range.
+    fn linear_memory_range<'a>(
+        &self,
+        address: i32,
+        message: &'a str,
+    ) -> Result<std::ops::Range<usize>, IndexOutOfBoundsError<'a>> {
+        let length = self.linear_memory.len();
+        match usize::try_from(address) {
+            Ok(start) if start + WORD_SIZE <= length => Ok(start..start + WORD_SIZE),
+            _ => Err(IndexOutOfBoundsError::new(address, length, message)),
+        }
+    }
+
+    pub fn linear_memory_load<'a>(
+        &self,
+        address: i32,
+        message: &'a str,
+    ) -> Result<i32, IndexOutOfBoundsError<'a>> {
+        let range = self.linear_memory_range(address, message)?;
+        let mut word = [0; WORD_SIZE];
+        word.copy_from_slice(&self.linear_memory[range]);
+        Ok(i32::from_le_bytes(word))
+    }
+
+    pub fn linear_memory_store<'a>(
+        &mut self,
+        address: i32,
+        value: i32,
+        message: &'a str,
+    ) -> Result<(), IndexOutOfBoundsError<'a>> {
+        let range = self.linear_memory_range(address, message)?;
+        self.linear_memory[range].copy_from_slice(&value.to_le_bytes());
+        Ok(())
     }
 
     pub fn get_command_pointer(&self) -> usize {
@@ -72,6 +280,163 @@ impl State {
         self.ret = value;
     }
 
+    pub fn set_return_values(&mut self, values: Vec<Value>) {
+        self.return_values = Some(values);
+    }
+
+    pub fn take_return_values(&mut self) -> Option<Vec<Value>> {
+        self.return_values.take()
+    }
+
+    /// Replaces the destination of output instructions, which is discarded
+    /// by default.
+    pub fn set_output_sink(&mut self, sink: Box<dyn OutputSink>) {
+        self.output = sink;
+    }
+
+    pub fn write_output<'a>(
+        &mut self,
+        text: &str,
+        message: &'a str,
+    ) -> Result<(), OutputError<'a>> {
+        self.output
+            .write_str(text)
+            .map_err(|_| OutputError::new(message))
+    }
+
+    /// Replaces the source of the `INPUT` instruction, stdin by default.
+    pub fn set_input_source(&mut self, source: Box<dyn InputSource>) {
+        self.input = source;
+    }
+
+    pub fn read_input(&mut self, message: &str) -> Result<i32, RuntimeError> {
+        match self.input.next_int() {
+            Ok(Some(value)) => Ok(value),
+            Ok(None) => Err(InputExhaustedError::new(message))?,
+            Err(_) => Err(InputError::new(message))?,
+        }
+    }
+
+    /// Restarts the random number sequence used by `RAND` and `RAND_RANGE`.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = Rng::new(seed);
+    }
+
+    pub fn get_rng(&mut self) -> &mut Rng {
+        &mut self.rng
+    }
+
+    /// Replaces the clock read by `TIME`, the system clock by default.
+    pub fn set_clock(&mut self, clock: Box<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    pub fn get_clock(&self) -> &dyn Clock {
+        self.clock.as_ref()
+    }
+
+    pub fn get_natives(&self) -> &NativeRegistry {
+        &self.natives
+    }
+
+    pub fn get_natives_mut(&mut self) -> &mut NativeRegistry {
+        &mut self.natives
+    }
+
+    /// Replaces the destination of `DUMP`, stderr by default.
+    pub fn set_debug_sink(&mut self, sink: Box<dyn OutputSink>) {
+        self.debug_output = sink;
+    }
+
+    pub fn write_debug<'a>(&mut self, text: &str, message: &'a str) -> Result<(), OutputError<'a>> {
+        self.debug_output
+            .write_str(text)
+            .map_err(|_| OutputError::new(message))
+    }
+
+    pub fn set_breakpoint_handler(&mut self, handler: BreakpointHandler) {
+        self.breakpoint_handler = Some(handler);
+    }
+
+    /// Hands control to the breakpoint handler, does nothing without one.
+    pub fn trigger_breakpoint(&mut self, message: &str) {
+        // Handler is taken out for the call, so it can get the state mutably
+        if let Some(mut handler) = self.breakpoint_handler.take() {
+            handler(self, message);
+            self.breakpoint_handler.get_or_insert(handler);
+        }
+    }
+
+    pub fn get_stack(&self) -> &[Value] {
+        &self.stack
+    }
+
+    pub fn get_memory(&self) -> &HashMap<String, Value> {
+        &self.memory
+    }
+
+    /// Human-readable snapshot of the stack, the variables and the loop.
+    pub fn describe(&self) -> String {
+        let mut variables: Vec<_> = self.memory.iter().collect();
+        variables.sort_by(|first, second| first.0.cmp(second.0));
+        let memory: Vec<String> = variables
+            .iter()
+            .map(|(name, value)| format!("{} = {:?}", name, value))
+            .collect();
+        let loop_instance = match &self.loop_instance {
+            Some(simpleloop) => format!("{:?}", simpleloop),
+            None => String::from("None"),
+        };
+        format!(
+            "stack: {:?}\nmemory: {{{}}}\nloop: {}\n",
+            self.stack,
+            memory.join(", "),
+            loop_instance
+        )
+    }
+
+    pub fn set_file_sandbox(&mut self, sandbox: FileSandbox) {
+        self.file_sandbox = Some(sandbox);
+    }
+
+    /// Returns the directory granted to file instructions.
+    pub fn get_file_sandbox<'a>(
+        &self,
+        message: &'a str,
+    ) -> Result<&FileSandbox, PermissionDeniedError<'a>> {
+        self.file_sandbox
+            .as_ref()
+            .ok_or(PermissionDeniedError::new(message))
+    }
+
+    pub fn is_big_int_mode(&self) -> bool {
+        self.big_int_mode
+    }
+
+    /// In big-integer mode integer arithmetic is carried out on `Value::BigInt`
+    /// and never overflows.
+    pub fn set_big_int_mode(&mut self, value: bool) {
+        self.big_int_mode = value;
+    }
+
+    pub fn get_decimal_scale(&self) -> u32 {
+        self.decimal_scale
+    }
+
+    /// Number of fractional digits kept in results of decimal commands.
+    pub fn set_decimal_scale(&mut self, value: u32) {
+        self.decimal_scale = value;
+    }
+
+    pub fn get_rounding_mode(&self) -> RoundingMode {
+        self.rounding_mode
+    }
+
+    /// Rounding used by decimal commands without an explicit rounding mode.
+    pub fn set_rounding_mode(&mut self, value: RoundingMode) {
+        self.rounding_mode = value;
+    }
+
     pub fn get_loop(&mut self) -> &mut Option<SimpleLoop> {
         &mut self.loop_instance
     }
@@ -0,0 +1,191 @@
+use std::iter::Peekable;
+use std::ops::Range;
+use std::str::CharIndices;
+
+/// Instruction name or argument of a line of code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token<'a> {
+    /// Token as written in the line, including quotes.
+    pub text: &'a str,
+    /// Token without quotes and with escape sequences replaced.
+    pub value: String,
+    /// Quote character of a quoted token.
+    pub quote: Option<char>,
+    /// Byte offset of `text` in the line.
+    pub start: usize,
+}
+
+impl Token<'_> {
+    /// Byte offset just past the end of `text` in the line.
+    pub fn end(&self) -> usize {
+        self.start + self.text.len()
+    }
+
+    /// Byte range of `text` in the line.
+    pub fn bytes(&self) -> Range<usize> {
+        self.start..self.end()
+    }
+}
+
+/// Malformed part of a line, each variant holds the byte range of the
+/// offending part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenizeError {
+    UnterminatedQuote(Range<usize>),
+    InvalidEscape(Range<usize>),
+    /// Closing quote followed by other characters, e.g. `"a"b`.
+    UnexpectedCharacter(Range<usize>),
+}
+
+fn is_separator(symbol: char) -> bool {
+    symbol.is_whitespace() || is_comment_start(symbol)
+}
+
+fn is_comment_start(symbol: char) -> bool {
+    matches!(symbol, '#' | ';')
+}
+
+/// Splits a line into whitespace-separated tokens. Arguments may be quoted
+/// with `"` or `'` to contain whitespaces and the escape sequences
+/// `\"`, `\'`, `\\`, `\n` and `\t`. A `#` or `;` outside of quotes starts a
+/// comment which lasts until the end of the line.
+pub fn tokenize(line: &str) -> Result<Vec<Token<'_>>, TokenizeError> {
+    let mut tokens = Vec::new();
+    let mut chars = line.char_indices().peekable();
+    while let Some(&(start, symbol)) = chars.peek() {
+        if symbol.is_whitespace() {
+            chars.next();
+        } else if is_comment_start(symbol) {
+            break;
+        } else if symbol == '"' || symbol == '\'' {
+            chars.next();
+            tokens.push(quoted_token(line, start, symbol, &mut chars)?);
+        } else {
+            let mut end = line.len();
+            while let Some(&(index, symbol)) = chars.peek() {
+                if is_separator(symbol) {
+                    end = index;
+                    break;
+                }
+                chars.next();
+            }
+            let text = &line[start..end];
+            tokens.push(Token {
+                text,
+                value: text.to_owned(),
+                quote: None,
+                start,
+            });
+        }
+    }
+    Ok(tokens)
+}
+
+fn quoted_token<'a>(
+    line: &'a str,
+    start: usize,
+    quote: char,
+    chars: &mut Peekable<CharIndices>,
+) -> Result<Token<'a>, TokenizeError> {
+    let mut value = String::new();
+    while let Some((index, symbol)) = chars.next() {
+        if symbol == quote {
+            if let Some(&(next, following)) = chars.peek() {
+                if !is_separator(following) {
+                    let unexpected = next..next + following.len_utf8();
+                    return Err(TokenizeError::UnexpectedCharacter(unexpected));
+                }
+            }
+            return Ok(Token {
+                text: &line[start..index + symbol.len_utf8()],
+                value,
+                quote: Some(quote),
+                start,
+            });
+        }
+        if symbol != '\\' {
+            value.push(symbol);
+            continue;
+        }
+        match chars.next() {
+            Some((_, '"')) => value.push('"'),
+            Some((_, '\'')) => value.push('\''),
+            Some((_, '\\')) => value.push('\\'),
+            Some((_, 'n')) => value.push('\n'),
+            Some((_, 't')) => value.push('\t'),
+            Some((escape, other)) => {
+                let sequence = index..escape + other.len_utf8();
+                return Err(TokenizeError::InvalidEscape(sequence));
+            }
+            None => break,
+        }
+    }
+    Err(TokenizeError::UnterminatedQuote(
+        start..line.trim_end().len(),
+    ))
+}
+
+/// Variable names start with a letter or `_` followed by letters, digits
+/// or `_`.
+pub fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|symbol| symbol.is_ascii_alphanumeric() || symbol == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(line: &str) -> Vec<String> {
+        tokenize(line)
+            .unwrap()
+            .into_iter()
+            .map(|token| token.value)
+            .collect()
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(values("  READ_VAR  x ; comment"), ["READ_VAR", "x"]);
+        assert_eq!(values("READ_VAR 'x'#comment"), ["READ_VAR", "x"]);
+        assert_eq!(
+            values(r#"LOAD_STR "a 'b' \"c\" # ;\n""#),
+            ["LOAD_STR", "a 'b' \"c\" # ;\n"]
+        );
+        assert_eq!(
+            values(r"FILE_APPEND 'my file.txt'"),
+            ["FILE_APPEND", "my file.txt"]
+        );
+        let tokens = tokenize("LOAD_STR \"\"").unwrap();
+        assert_eq!(tokens[1].text, "\"\"");
+        assert_eq!(tokens[1].quote, Some('"'));
+        assert_eq!(tokens[1].bytes(), 9..11);
+    }
+
+    #[test]
+    fn test_tokenize_errors() {
+        assert_eq!(
+            tokenize("LOAD_STR \"abc  "),
+            Err(TokenizeError::UnterminatedQuote(9..13))
+        );
+        assert_eq!(
+            tokenize(r"LOAD_STR 'a\qb'"),
+            Err(TokenizeError::InvalidEscape(11..13))
+        );
+        assert_eq!(
+            tokenize("WRITE_VAR 'a'b"),
+            Err(TokenizeError::UnexpectedCharacter(13..14))
+        );
+    }
+
+    #[test]
+    fn test_is_identifier() {
+        assert!(is_identifier("_counter2"));
+        assert!(!is_identifier("2counter"));
+        assert!(!is_identifier("my var"));
+        assert!(!is_identifier(""));
+    }
+}
@@ -0,0 +1,93 @@
+use crate::bigint::BigInt;
+use crate::decimal::Decimal;
+use crate::heap::HeapRef;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i32),
+    BigInt(BigInt),
+    Decimal(Decimal),
+    Float(f64),
+    Bool(bool),
+    Str(String),
+    Array(HeapRef),
+    Map(HeapRef),
+}
+
+impl Value {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Int(_) => "Int",
+            Value::BigInt(_) => "BigInt",
+            Value::Decimal(_) => "Decimal",
+            Value::Float(_) => "Float",
+            Value::Bool(_) => "Bool",
+            Value::Str(_) => "Str",
+            Value::Array(_) => "Array",
+            Value::Map(_) => "Map",
+        }
+    }
+}
+
+impl From<i32> for Value {
+    fn from(value: i32) -> Self {
+        Value::Int(value)
+    }
+}
+
+/// Values which fit into i32 become `Value::Int`, so results of big-integer
+/// arithmetic can still be used as array indices or map keys.
+impl From<BigInt> for Value {
+    fn from(value: BigInt) -> Self {
+        match value.to_i32() {
+            Some(value) => Value::Int(value),
+            None => Value::BigInt(value),
+        }
+    }
+}
+
+impl From<Decimal> for Value {
+    fn from(value: Decimal) -> Self {
+        Value::Decimal(value)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Float(value)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::Str(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::Str(value.to_owned())
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Int(value) => write!(formatter, "{}", value),
+            Value::BigInt(value) => write!(formatter, "{}", value),
+            Value::Decimal(value) => write!(formatter, "{}", value),
+            Value::Float(value) => write!(formatter, "{:?}", value),
+            Value::Bool(value) => write!(formatter, "{}", value),
+            Value::Str(value) => write!(formatter, "{}", value),
+            Value::Array(handle) => write!(formatter, "<array #{}>", handle.index()),
+            Value::Map(handle) => write!(formatter, "<map #{}>", handle.index()),
+        }
+    }
+}
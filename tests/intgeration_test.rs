@@ -1,4 +1,6 @@
-use interpreter::{parser::parse, state::State, simpleloop::SimpleLoop, bytecode::ByteCode};
+use interpreter::io::FileSource;
+use interpreter::parser::{parse_with_options, ParserOptions};
+use interpreter::{parser::parse, state::State, simpleloop::SimpleLoop, bytecode::ByteCode, value::Value, bigint::BigInt, decimal::Decimal};
 
 #[test]
 fn integeration_loop_test() {
@@ -9,7 +11,7 @@ fn integeration_loop_test() {
     let state: State = State::new(Some(simpleloop));
     let mut interpreter = ByteCode::new(state, result);
     let result = interpreter.run().unwrap().unwrap();
-    assert_eq!(result, 5); 
+    assert_eq!(result, Value::Int(5)); 
 }
 
 #[test]
@@ -21,7 +23,7 @@ fn integration_arithmetic_test() {
     let state: State = State::new(Some(simpleloop));
     let mut interpreter = ByteCode::new(state, result);
     let result = interpreter.run().unwrap().unwrap();
-    assert_eq!(result, -6); 
+    assert_eq!(result, Value::Int(-6)); 
 }
 
 #[test]
@@ -33,7 +35,413 @@ fn integration_example_test() {
     let state: State = State::new(Some(simpleloop));
     let mut interpreter = ByteCode::new(state, result);
     let result = interpreter.run().unwrap().unwrap();
-    assert_eq!(result, 4); 
+    assert_eq!(result, Value::Int(4)); 
+}
+
+#[test]
+fn integration_float_test() {
+    let file = "tests/inputs/example_float.code";
+    let input = std::fs::read_to_string(file).unwrap();
+    let result = parse(input).unwrap();
+    let simpleloop = SimpleLoop::new();
+    let state: State = State::new(Some(simpleloop));
+    let mut interpreter = ByteCode::new(state, result);
+    let result = interpreter.run().unwrap().unwrap();
+    assert_eq!(result, Value::Float(2.5)); 
+}
+
+#[test]
+fn integration_bigint_test() {
+    let file = "tests/inputs/example_bigint.code";
+    let input = std::fs::read_to_string(file).unwrap();
+    let options = ParserOptions { big_int: true, ..Default::default() };
+    let result = parse_with_options(input, &options).unwrap();
+    let simpleloop = SimpleLoop::new();
+    let mut state: State = State::new(Some(simpleloop));
+    state.set_big_int_mode(true);
+    let mut interpreter = ByteCode::new(state, result);
+    let result = interpreter.run().unwrap().unwrap();
+    let expected: BigInt = "265121435515141626551514162654886294829".parse().unwrap();
+    assert_eq!(result, Value::BigInt(expected)); 
+}
+
+#[test]
+#[should_panic]
+fn integration_bigint_without_mode_test() {
+    let file = "tests/inputs/example_bigint.code";
+    let input = std::fs::read_to_string(file).unwrap();
+    let result = parse(input).unwrap();
+    let simpleloop = SimpleLoop::new();
+    let state: State = State::new(Some(simpleloop));
+    let mut interpreter = ByteCode::new(state, result);
+    interpreter.run().unwrap();
+}
+
+#[test]
+fn integration_decimal_test() {
+    let file = "tests/inputs/example_decimal.code";
+    let input = std::fs::read_to_string(file).unwrap();
+    let result = parse(input).unwrap();
+    let simpleloop = SimpleLoop::new();
+    let state: State = State::new(Some(simpleloop));
+    let mut interpreter = ByteCode::new(state, result);
+    let result = interpreter.run().unwrap().unwrap();
+    assert_eq!(result, Value::Decimal(Decimal::new(6477, 2))); 
+}
+
+#[test]
+fn integration_string_test() {
+    let file = "tests/inputs/example_string.code";
+    let input = std::fs::read_to_string(file).unwrap();
+    let result = parse(input).unwrap();
+    let simpleloop = SimpleLoop::new();
+    let state: State = State::new(Some(simpleloop));
+    let mut interpreter = ByteCode::new(state, result);
+    let result = interpreter.run().unwrap().unwrap();
+    assert_eq!(result, Value::Str(String::from("Total: \"42\" items"))); 
+}
+
+#[test]
+fn integration_array_test() {
+    let file = "tests/inputs/example_array.code";
+    let input = std::fs::read_to_string(file).unwrap();
+    let result = parse(input).unwrap();
+    let simpleloop = SimpleLoop::new();
+    let state: State = State::new(Some(simpleloop));
+    let mut interpreter = ByteCode::new(state, result);
+    let result = interpreter.run().unwrap().unwrap();
+    assert_eq!(result, Value::Int(15)); 
+}
+
+#[test]
+fn integration_map_test() {
+    let file = "tests/inputs/example_map.code";
+    let input = std::fs::read_to_string(file).unwrap();
+    let result = parse(input).unwrap();
+    let simpleloop = SimpleLoop::new();
+    let state: State = State::new(Some(simpleloop));
+    let mut interpreter = ByteCode::new(state, result);
+    let result = interpreter.run().unwrap().unwrap();
+    assert_eq!(result, Value::Int(12)); 
+}
+
+#[test]
+fn integration_input_test() {
+    let file = "tests/inputs/example_input.code";
+    let input = std::fs::read_to_string(file).unwrap();
+    let result = parse(input).unwrap();
+    let simpleloop = SimpleLoop::new();
+    let mut state: State = State::new(Some(simpleloop));
+    state.set_input_source(Box::new(FileSource::open("tests/inputs/example_input.txt").unwrap()));
+    let mut interpreter = ByteCode::new(state, result);
+    let result = interpreter.run().unwrap().unwrap();
+    assert_eq!(result, Value::Int(60)); 
+}
+
+#[test]
+#[should_panic]
+fn integration_assert_test() {
+    let file = "tests/inputs/example_assert.code";
+    let input = std::fs::read_to_string(file).unwrap();
+    let result = parse(input).unwrap();
+    let simpleloop = SimpleLoop::new();
+    let state: State = State::new(Some(simpleloop));
+    let mut interpreter = ByteCode::new(state, result);
+    interpreter.run().unwrap();
+}
+
+#[test]
+fn integration_strip_asserts_test() {
+    let file = "tests/inputs/example_assert.code";
+    let input = std::fs::read_to_string(file).unwrap();
+    let options = ParserOptions { strip_asserts: true, ..Default::default() };
+    let result = parse_with_options(input, &options).unwrap();
+    let simpleloop = SimpleLoop::new();
+    let state: State = State::new(Some(simpleloop));
+    let mut interpreter = ByteCode::new(state, result);
+    let result = interpreter.run().unwrap().unwrap();
+    assert_eq!(result, Value::Int(3)); 
+}
+
+#[test]
+fn integration_strip_asserts_operands_test() {
+    let file = "tests/inputs/example_assert_operands.code";
+    let input = std::fs::read_to_string(file).unwrap();
+    for strip_asserts in [false, true] {
+        let options = ParserOptions { strip_asserts, ..Default::default() };
+        let result = parse_with_options(input.clone(), &options).unwrap();
+        let simpleloop = SimpleLoop::new();
+        let state: State = State::new(Some(simpleloop));
+        let mut interpreter = ByteCode::new(state, result);
+        let result = interpreter.run().unwrap().unwrap();
+        assert_eq!(result, Value::Int(5)); 
+    }
+}
+
+#[test]
+fn integration_parse_errors_test() {
+    let file = "tests/inputs/example_parse_errors.code";
+    let input = std::fs::read_to_string(file).unwrap();
+    let errors = match parse(input) {
+        Ok(_) => panic!("code with errors was parsed"),
+        Err(errors) => errors,
+    };
+    assert_eq!(errors.len(), 3);
+    assert!(errors[0].to_string().contains("2:     LOAD_VAL"));
+    assert!(errors[1].to_string().contains("4:     MULTIPLY"));
+    assert!(errors[2].to_string().contains("6:     LOOP"));
+
+    let span = errors[0].span().unwrap();
+    assert_eq!((span.file(), span.line(), span.columns()), ("<input>", 2, 9..12));
+    assert_eq!(
+        errors[0].render(),
+        "error: UknownCommandError: The command is unkonwn or syntax is wrong\n --> <input>:2:10\n  |\n2 | LOAD_VAL one\n  |          ^^^"
+    );
+}
+
+#[test]
+fn integration_extra_arguments_test() {
+    let file = "tests/inputs/example_extra_args.code";
+    let input = std::fs::read_to_string(file).unwrap();
+    let result = parse(input.clone()).unwrap();
+    let simpleloop = SimpleLoop::new();
+    let state: State = State::new(Some(simpleloop));
+    let mut interpreter = ByteCode::new(state, result);
+    let result = interpreter.run().unwrap().unwrap();
+    assert_eq!(result, Value::Int(5)); 
+
+    let options = ParserOptions { strict: true, ..Default::default() };
+    let errors = match parse_with_options(input, &options) {
+        Ok(_) => panic!("extra argument was accepted in strict mode"),
+        Err(errors) => errors,
+    };
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].to_string().contains("ADD takes 0 argument(s) but 1 were given"));
+    assert_eq!(errors[0].span().unwrap().columns(), 4..5);
+}
+
+#[test]
+fn integration_strict_arguments_test() {
+    let file = "tests/inputs/example_arity_errors.code";
+    let input = std::fs::read_to_string(file).unwrap();
+    let options = ParserOptions { strict: true, ..Default::default() };
+    let errors = match parse_with_options(input, &options) {
+        Ok(_) => panic!("code with errors was parsed"),
+        Err(errors) => errors,
+    };
+    assert_eq!(errors.len(), 3);
+    assert!(errors[0].to_string().contains("LOOP takes 1 argument(s) but 0 were given"));
+    assert!(errors[1].to_string().contains("Argument of LOAD_VAL must be an integer but found ten"));
+    assert!(errors[2].to_string().contains("DMUL takes 0 to 1 argument(s) but 3 were given"));
+    assert_eq!(errors[2].span().unwrap().columns(), 13..23);
+}
+
+#[test]
+fn integration_runtime_error_span_test() {
+    let file = "tests/inputs/example_assert.code";
+    let input = std::fs::read_to_string(file).unwrap();
+    let options = ParserOptions { file_name: Some(file.to_owned()), ..Default::default() };
+    let result = parse_with_options(input, &options).unwrap();
+    let simpleloop = SimpleLoop::new();
+    let state: State = State::new(Some(simpleloop));
+    let mut interpreter = ByteCode::new(state, result);
+    let error = interpreter.run().unwrap_err();
+    let span = error.span().unwrap();
+    assert_eq!((span.file(), span.line(), span.columns()), (file, 8, 0..9));
+    assert!(error.render().ends_with("8 | ASSERT_EQ\n  | ^^^^^^^^^"));
+}
+
+#[test]
+fn integration_quoted_arguments_test() {
+    let file = "tests/inputs/example_quoted.code";
+    let input = std::fs::read_to_string(file).unwrap();
+    let result = parse(input).unwrap();
+    let simpleloop = SimpleLoop::new();
+    let state: State = State::new(Some(simpleloop));
+    let mut interpreter = ByteCode::new(state, result);
+    let result = interpreter.run().unwrap().unwrap();
+    assert_eq!(result, Value::Int(18)); 
+}
+
+#[test]
+fn integration_quote_errors_test() {
+    let file = "tests/inputs/example_quote_errors.code";
+    let input = std::fs::read_to_string(file).unwrap();
+    let options = ParserOptions { strict: true, ..Default::default() };
+    let errors = match parse_with_options(input, &options) {
+        Ok(_) => panic!("code with errors was parsed"),
+        Err(errors) => errors,
+    };
+    assert_eq!(errors.len(), 3);
+    assert!(errors[0].to_string().contains("UnterminatedQuoteError"));
+    assert_eq!(errors[0].span().unwrap().columns(), 9..22);
+    assert!(errors[1].to_string().contains("\"my var\" is not a valid variable name"));
+    assert!(errors[2].to_string().contains("\"1x\" is not a valid variable name"));
+}
+
+#[test]
+fn integration_lenient_names_test() {
+    let file = "tests/inputs/example_lenient_names.code";
+    let input = std::fs::read_to_string(file).unwrap();
+    let result = parse(input).unwrap();
+    let simpleloop = SimpleLoop::new();
+    let state: State = State::new(Some(simpleloop));
+    let mut interpreter = ByteCode::new(state, result);
+    let result = interpreter.run().unwrap().unwrap();
+    assert_eq!(result, Value::Int(1)); 
+}
+
+#[test]
+fn integration_literals_test() {
+    let file = "tests/inputs/example_literals.code";
+    let input = std::fs::read_to_string(file).unwrap();
+    let result = parse(input).unwrap();
+    let simpleloop = SimpleLoop::new();
+    let state: State = State::new(Some(simpleloop));
+    let mut interpreter = ByteCode::new(state, result);
+    let result = interpreter.run().unwrap().unwrap();
+    assert_eq!(result, Value::Int(1_000_329)); 
+}
+
+#[test]
+fn integration_literal_errors_test() {
+    let file = "tests/inputs/example_literal_errors.code";
+    let input = std::fs::read_to_string(file).unwrap();
+    let errors = match parse(input) {
+        Ok(_) => panic!("code with errors was parsed"),
+        Err(errors) => errors,
+    };
+    assert_eq!(errors.len(), 6);
+    assert!(errors[0].to_string().contains("LiteralOutOfRangeError"));
+    assert_eq!(errors[0].span().unwrap().columns(), 5..18);
+    assert!(errors[1].to_string().contains("LiteralOutOfRangeError"));
+    assert_eq!(errors[1].span().unwrap().columns(), 13..26);
+    assert!(errors[2].to_string().contains("UknownCommandError"));
+    assert!(errors[3].to_string().contains("LiteralOutOfRangeError"));
+    assert!(errors[3].to_string().contains("5:     LOAD_VAL 3000000000"));
+    assert!(errors[4].to_string().contains("LiteralOutOfRangeError"));
+    assert!(errors[5].message().contains("low 5 is above high 1"));
+    assert_eq!(errors[5].span().unwrap().columns(), 11..14);
+}
+
+#[test]
+fn integration_float_literal_test() {
+    let file = "tests/inputs/example_float_literal.code";
+    let input = std::fs::read_to_string(file).unwrap();
+    let options = ParserOptions { strict: true, ..Default::default() };
+    let result = parse_with_options(input, &options).unwrap();
+    let simpleloop = SimpleLoop::new();
+    let state: State = State::new(Some(simpleloop));
+    let mut interpreter = ByteCode::new(state, result);
+    let result = interpreter.run().unwrap().unwrap();
+    assert_eq!(result, Value::Float(1_099_511_627_775.0)); 
+}
+
+#[test]
+fn integration_const_test() {
+    let file = "tests/inputs/example_const.code";
+    let input = std::fs::read_to_string(file).unwrap();
+    let result = parse(input).unwrap();
+    let simpleloop = SimpleLoop::new();
+    let state: State = State::new(Some(simpleloop));
+    let mut interpreter = ByteCode::new(state, result);
+    let result = interpreter.run().unwrap().unwrap();
+    assert_eq!(result, Value::Int(15)); 
+}
+
+#[test]
+fn integration_const_keywords_test() {
+    let file = "tests/inputs/example_const_keywords.code";
+    for strict in [false, true] {
+        let input = std::fs::read_to_string(file).unwrap();
+        let options = ParserOptions { strict, ..Default::default() };
+        let result = parse_with_options(input, &options).unwrap();
+        let simpleloop = SimpleLoop::new();
+        let state: State = State::new(Some(simpleloop));
+        let mut interpreter = ByteCode::new(state, result);
+        let result = interpreter.run_values().unwrap();
+        assert_eq!(result, vec![Value::Decimal("3.34".parse::<Decimal>().unwrap()), Value::Int(3)]); 
+    }
+}
+
+#[test]
+fn integration_const_errors_test() {
+    let file = "tests/inputs/example_const_errors.code";
+    let input = std::fs::read_to_string(file).unwrap();
+    let errors = match parse(input) {
+        Ok(_) => panic!("code with errors was parsed"),
+        Err(errors) => errors,
+    };
+    assert_eq!(errors.len(), 4);
+    assert!(errors[0].to_string().contains("Constant RATE is already defined"));
+    assert_eq!(errors[0].span().unwrap().columns(), 7..11);
+    assert!(errors[1].to_string().contains("Argument of .const must be a constant name but found 2X"));
+    assert!(errors[2].to_string().contains(".const takes 2 argument(s) but 1 were given"));
+    assert!(errors[3].to_string().contains("5:     LOAD_VAL UNDEFINED"));
+}
+
+#[test]
+fn integration_comments_test() {
+    let file = "tests/inputs/example_comments.code";
+    let input = std::fs::read_to_string(file).unwrap();
+    let result = parse(input).unwrap();
+    let simpleloop = SimpleLoop::new();
+    let state: State = State::new(Some(simpleloop));
+    let mut interpreter = ByteCode::new(state, result);
+    let result = interpreter.run().unwrap().unwrap();
+    assert_eq!(result, Value::from("#1; hello \"world\" # ; ")); 
+}
+
+fn run_file_io_example(file: &str, root: Option<&std::path::Path>) -> Result<Option<Value>, String> {
+    let input = std::fs::read_to_string(file).unwrap();
+    let result = parse(input).unwrap();
+    let simpleloop = SimpleLoop::new();
+    let state: State = State::new(Some(simpleloop));
+    let mut interpreter = ByteCode::new(state, result);
+    if let Some(root) = root {
+        interpreter.grant_file_access(root);
+    }
+    interpreter.run().map_err(|error| error.to_string())
+}
+
+#[test]
+fn integration_file_io_test() {
+    let root = std::env::temp_dir().join(format!("bytecode_file_io_{}", std::process::id()));
+    std::fs::create_dir_all(&root).unwrap();
+    std::fs::write(root.join("counter.txt"), "41\n").unwrap();
+
+    let result = run_file_io_example("tests/inputs/example_file_io.code", Some(&root));
+    let log = std::fs::read_to_string(root.join("log.txt"));
+    std::fs::remove_dir_all(&root).unwrap();
+    assert_eq!(result.unwrap().unwrap(), Value::Int(42));
+    assert_eq!(log.unwrap(), "42\n");
+}
+
+#[test]
+fn integration_file_io_permission_test() {
+    let root = std::env::temp_dir().join(format!("bytecode_file_io_denied_{}", std::process::id()));
+    std::fs::create_dir_all(&root).unwrap();
+
+    let without_grant = run_file_io_example("tests/inputs/example_file_io.code", None);
+    let outside_root = run_file_io_example("tests/inputs/example_file_io_escape.code", Some(&root));
+    std::fs::remove_dir_all(&root).unwrap();
+    assert!(without_grant.unwrap_err().contains("PermissionDeniedError"));
+    assert!(outside_root.unwrap_err().contains("PermissionDeniedError"));
+}
+
+#[test]
+#[cfg(unix)]
+fn integration_file_io_dangling_symlink_test() {
+    let root = std::env::temp_dir().join(format!("bytecode_file_io_symlink_{}", std::process::id()));
+    let outside = std::env::temp_dir().join(format!("bytecode_file_io_outside_{}.txt", std::process::id()));
+    std::fs::create_dir_all(&root).unwrap();
+    std::os::unix::fs::symlink(&outside, root.join("log.txt")).unwrap();
+
+    let result = run_file_io_example("tests/inputs/example_file_io_symlink.code", Some(&root));
+    let outside_exists = outside.exists();
+    std::fs::remove_dir_all(&root).unwrap();
+    assert!(result.unwrap_err().contains("PermissionDeniedError"));
+    assert!(!outside_exists);
 }
 
 #[test]
@@ -46,7 +454,7 @@ fn integration_empty_code_test() {
     let state: State = State::new(Some(simpleloop));
     let mut interpreter = ByteCode::new(state, result);
     let result = interpreter.run().unwrap().unwrap();
-    assert_eq!(result, 4); 
+    assert_eq!(result, Value::Int(4)); 
 }
 
 #[test]
@@ -59,7 +467,34 @@ fn integration_loop_error_test() {
     let state: State = State::new(Some(simpleloop));
     let mut interpreter = ByteCode::new(state, result);
     let result = interpreter.run().unwrap().unwrap();
-    assert_eq!(result, 4); 
+    assert_eq!(result, Value::Int(4)); 
 }
 
 
+
+#[test]
+fn integration_diagnostics_test() {
+    let file = "tests/inputs/example_diagnostics.code";
+    let input = std::fs::read_to_string(file).unwrap();
+    let options = ParserOptions { strict: true, ..Default::default() };
+    let errors = match parse_with_options(input, &options) {
+        Ok(_) => panic!("code with errors was parsed"),
+        Err(errors) => errors,
+    };
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].message().ends_with("found \"x LINE => y\"."));
+    assert!(errors[0].render().starts_with(&format!("error: {}\n", errors[0].message())));
+}
+
+#[test]
+fn integration_link_error_span_test() {
+    let file = "tests/inputs/example_unknown_native.code";
+    let input = std::fs::read_to_string(file).unwrap();
+    let result = parse(input).unwrap();
+    let simpleloop = SimpleLoop::new();
+    let state: State = State::new(Some(simpleloop));
+    let mut interpreter = ByteCode::new(state, result);
+    let error = interpreter.run().unwrap_err();
+    assert!(error.to_string().contains("2:     CALL_NATIVE missing"));
+    assert!(error.render().ends_with("2 | CALL_NATIVE missing\n  | ^^^^^^^^^^^^^^^^^^^"));
+}